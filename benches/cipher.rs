@@ -0,0 +1,136 @@
+use aes::{
+    cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit},
+    Aes128, Aes256,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dnac::{
+    analysis::random_dna,
+    dna::{binary_to_DNA, DNA},
+    modes, DNAC,
+};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::hint::black_box;
+
+const BULK_SIZE: usize = 1 << 16;
+const ROUNDS: [usize; 6] = [4, 8, 12, 16, 22, 32];
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
+
+fn random_bytes(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let mut bytes = vec![0; size];
+    rng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn to_dna(bytes: &[u8]) -> Vec<DNA> {
+    bytes.iter().flat_map(binary_to_DNA).collect()
+}
+
+fn aes_encrypt<C: BlockEncrypt>(cipher: &C, data: &mut [u8]) {
+    for block in data.chunks_exact_mut(16) {
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+    }
+}
+
+fn key_setup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("key setup");
+    let key = random_bytes(&mut rng(), 32);
+    for size in [16, 32] {
+        let dna_key = to_dna(&key[..size]);
+        group.bench_with_input(BenchmarkId::new("dnac", size), &dna_key, |b, key| {
+            b.iter(|| DNAC::new_default(black_box(key.clone())))
+        });
+    }
+    group.bench_function(BenchmarkId::new("aes", 16), |b| {
+        b.iter(|| Aes128::new(GenericArray::from_slice(black_box(&key[..16]))))
+    });
+    group.bench_function(BenchmarkId::new("aes", 32), |b| {
+        b.iter(|| Aes256::new(GenericArray::from_slice(black_box(&key))))
+    });
+    group.finish();
+}
+
+fn single_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single block");
+    let rng = &mut rng();
+    let dnac = DNAC::new_default(random_dna(rng, 32));
+    let text = random_dna(rng, 16);
+    let encrypted = dnac.encrypt(text.clone());
+    group.bench_function("dnac encrypt", |b| {
+        b.iter(|| dnac.encrypt(black_box(text.clone())))
+    });
+    group.bench_function("dnac decrypt", |b| {
+        b.iter(|| dnac.decrypt(black_box(encrypted.clone())))
+    });
+    let constant_time = DNAC::new_default(random_dna(rng, 32)).constant_time();
+    group.bench_function("dnac constant-time encrypt", |b| {
+        b.iter(|| constant_time.encrypt(black_box(text.clone())))
+    });
+
+    let key = random_bytes(rng, 32);
+    let block = GenericArray::clone_from_slice(&random_bytes(rng, 16));
+    let aes128 = Aes128::new(GenericArray::from_slice(&key[..16]));
+    let aes256 = Aes256::new(GenericArray::from_slice(&key));
+    group.bench_function("aes128 encrypt", |b| {
+        b.iter(|| aes128.encrypt_block(&mut black_box(block)))
+    });
+    group.bench_function("aes128 decrypt", |b| {
+        b.iter(|| aes128.decrypt_block(&mut black_box(block)))
+    });
+    group.bench_function("aes256 encrypt", |b| {
+        b.iter(|| aes256.encrypt_block(&mut black_box(block)))
+    });
+    group.bench_function("aes256 decrypt", |b| {
+        b.iter(|| aes256.decrypt_block(&mut black_box(block)))
+    });
+    group.finish();
+}
+
+fn bulk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk");
+    group.throughput(Throughput::Bytes(BULK_SIZE as u64));
+    group.sample_size(10);
+    let rng = &mut rng();
+    let dnac = DNAC::new_default(random_dna(rng, 32));
+    let iv = random_dna(rng, 16);
+    let data = random_bytes(rng, BULK_SIZE);
+    let dna = to_dna(&data);
+    group.bench_function("dnac ecb", |b| b.iter(|| dnac.encrypt(dna.clone())));
+    group.bench_function("dnac cbc", |b| {
+        b.iter(|| modes::cbc_encrypt(&dnac, &iv, dna.clone()))
+    });
+    group.bench_function("dnac ctr", |b| {
+        b.iter(|| modes::ctr(&dnac, &iv, dna.clone()))
+    });
+
+    let key = random_bytes(rng, 32);
+    let aes128 = Aes128::new(GenericArray::from_slice(&key[..16]));
+    let aes256 = Aes256::new(GenericArray::from_slice(&key));
+    group.bench_function("aes128 ecb", |b| {
+        b.iter(|| aes_encrypt(&aes128, &mut data.clone()))
+    });
+    group.bench_function("aes256 ecb", |b| {
+        b.iter(|| aes_encrypt(&aes256, &mut data.clone()))
+    });
+    group.finish();
+}
+
+fn rounds(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rounds");
+    group.throughput(Throughput::Bytes(BULK_SIZE as u64 / 16));
+    let rng = &mut rng();
+    let key = random_dna(rng, 32);
+    let dna = random_dna(rng, BULK_SIZE / 16);
+    for rounds in ROUNDS {
+        let dnac = DNAC::new(key.clone(), rounds);
+        group.bench_with_input(BenchmarkId::new("dnac ecb", rounds), &dna, |b, dna| {
+            b.iter(|| dnac.encrypt(dna.clone()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, key_setup, single_block, bulk, rounds);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+#![no_main]
+
+use dnac::{
+    dna::{binary_to_DNA, DNA},
+    modes::{self, Mode},
+    DNAC,
+};
+use libfuzzer_sys::fuzz_target;
+
+fn to_dna(bytes: &[u8]) -> Vec<DNA> {
+    bytes.iter().flat_map(binary_to_DNA).collect()
+}
+
+// key and input files as read by `dnac decrypt`: any of them may be malformed,
+// which must be reported as an error
+fuzz_target!(|input: (Vec<u8>, Vec<u8>, Vec<u8>, u8)| {
+    let (key, iv, ciphertext, rounds) = input;
+    if let Ok(cipher) = DNAC::try_new(to_dna(&key), rounds as usize % 64) {
+        for mode in Mode::ALL {
+            let _ = modes::decrypt(&cipher, mode, &to_dna(&iv), to_dna(&ciphertext));
+        }
+    }
+});
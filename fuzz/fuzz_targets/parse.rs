@@ -0,0 +1,21 @@
+#![no_main]
+
+use dnac::{
+    analysis::{nist::ParsedReport, parse_bases, parse_hex},
+    kat, mct,
+};
+use libfuzzer_sys::fuzz_target;
+
+// text formats read by the binaries and the self tests
+fuzz_target!(|text: &str| {
+    let _ = parse_hex(text);
+    let _ = parse_bases(text);
+    let _ = ParsedReport::parse(text);
+    let _ = mct::parse(text);
+    if let Ok(vectors) = kat::parse(text) {
+        // round keys are allocated up front, so huge round counts only exhaust memory
+        for (mode, vector) in vectors.into_iter().filter(|(_, v)| v.rounds <= 64) {
+            let _ = vector.check(mode);
+        }
+    }
+});
@@ -1,11 +1,10 @@
 #! /usr/bin/env bash
 
-for input in $(ls dnac/*_1.txt); do
-        ./assess 1048576 < $input
-        mv "experiments/AlgorithmTesting/finalAnalysisReport.txt" "${input}_finalAnalysisReport"
-done
-
-for input in $(ls dnac/*_2.txt); do
-        ./assess 1056896 < $input
-        mv "experiments/AlgorithmTesting/finalAnalysisReport.txt" "${input}_finalAnalysisReport"
+# runs the reference `assess` binary on datasets generated by `nist`,
+# the sequence length is read from the metadata written next to every dataset
+for input in $(ls ${1:-dnac}/*.assess); do
+        dataset="${input%.assess}"
+        length=$(grep -o '"sequence_length":[0-9]*' "${dataset}.json" | cut -d: -f2)
+        ./assess "$length" < "$input"
+        mv "experiments/AlgorithmTesting/finalAnalysisReport.txt" "${dataset}_finalAnalysisReport"
 done
@@ -0,0 +1,165 @@
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes128, Aes256,
+};
+
+use super::Block;
+use crate::{
+    dna::{binary_to_DNA, DNA_to_binary, DNA},
+    key_schedule::{schedule_by_name, AesSchedule, KeySchedule},
+    DNAC,
+};
+
+/// Block cipher which can be run through the statistical tests.
+pub trait BlockCipherUnderTest: Sync {
+    fn name(&self) -> String;
+    fn encrypt_block(&self, block: Block) -> Block;
+}
+
+/// DNAC working on binary blocks.
+pub struct BlockDNAC {
+    cipher: DNAC,
+    rounds: usize,
+    schedule: &'static str,
+}
+
+impl BlockDNAC {
+    /// Returns an error for keys rejected by [`DNAC::try_new`].
+    pub fn new(key: &[u8], rounds: usize) -> Result<Self, String> {
+        BlockDNAC::with_schedule(key, rounds, &AesSchedule)
+    }
+
+    /// Returns an error for key sizes `schedule` does not support.
+    pub fn with_schedule(
+        key: &[u8],
+        rounds: usize,
+        schedule: &dyn KeySchedule,
+    ) -> Result<Self, String> {
+        let key = key.iter().flat_map(binary_to_DNA).collect();
+        Ok(BlockDNAC {
+            cipher: DNAC::with_schedule(key, rounds, schedule)?,
+            rounds,
+            schedule: schedule.name(),
+        })
+    }
+
+    pub fn encrypt(&self, bits: Block) -> Block {
+        let input = bits.iter().flat_map(binary_to_DNA).collect::<Vec<DNA>>();
+        self.cipher
+            .encrypt(input)
+            .chunks_exact(4)
+            .map(|chunk| DNA_to_binary(chunk.try_into().unwrap()))
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
+}
+
+impl BlockCipherUnderTest for BlockDNAC {
+    fn name(&self) -> String {
+        match self.schedule {
+            "aes" => format!("dnac-{}", self.rounds),
+            schedule => format!("dnac-{}-{}", schedule, self.rounds),
+        }
+    }
+
+    fn encrypt_block(&self, block: Block) -> Block {
+        self.encrypt(block)
+    }
+}
+
+impl BlockCipherUnderTest for Aes128 {
+    fn name(&self) -> String {
+        "aes128".to_string()
+    }
+
+    fn encrypt_block(&self, block: Block) -> Block {
+        let mut block = GenericArray::from(block);
+        BlockEncrypt::encrypt_block(self, &mut block);
+        block.into()
+    }
+}
+
+impl BlockCipherUnderTest for Aes256 {
+    fn name(&self) -> String {
+        "aes256".to_string()
+    }
+
+    fn encrypt_block(&self, block: Block) -> Block {
+        let mut block = GenericArray::from(block);
+        BlockEncrypt::encrypt_block(self, &mut block);
+        block.into()
+    }
+}
+
+/// Deliberately weak cipher (rotation and xor with the key) - every test should fail for it.
+pub struct ToyCipher {
+    key: Block,
+}
+
+impl ToyCipher {
+    pub fn new(key: &Block) -> Self {
+        ToyCipher { key: *key }
+    }
+}
+
+impl BlockCipherUnderTest for ToyCipher {
+    fn name(&self) -> String {
+        "toy".to_string()
+    }
+
+    fn encrypt_block(&self, block: Block) -> Block {
+        (u128::from_be_bytes(block).rotate_left(1) ^ u128::from_be_bytes(self.key)).to_be_bytes()
+    }
+}
+
+/// Cipher selected by name: "dnac", "dnac-ctr", "dnac-sponge" (DNAC with given key schedule),
+/// "aes128", "aes256" or "toy"; AES and the toy cipher use the first 16 or 32 bytes of `key`,
+/// DNAC the whole key.
+pub fn cipher_by_name(
+    name: &str,
+    key: &[u8],
+    rounds: usize,
+) -> Result<Box<dyn BlockCipherUnderTest>, String> {
+    match name {
+        "dnac" => Ok(Box::new(BlockDNAC::new(key, rounds)?)),
+        _ if name.starts_with("dnac-") => {
+            let schedule = schedule_by_name(&name["dnac-".len()..])?;
+            Ok(Box::new(BlockDNAC::with_schedule(
+                key,
+                rounds,
+                schedule.as_ref(),
+            )?))
+        }
+        "aes128" => Ok(Box::new(Aes128::new(GenericArray::from_slice(key_prefix(
+            name, key, 16,
+        )?)))),
+        "aes256" => Ok(Box::new(Aes256::new(GenericArray::from_slice(key_prefix(
+            name, key, 32,
+        )?)))),
+        "toy" => Ok(Box::new(ToyCipher::new(
+            key_prefix(name, key, 16)?.try_into().unwrap(),
+        ))),
+        _ => Err(format!("unknown cipher {}", name)),
+    }
+}
+
+fn key_prefix<'a>(name: &str, key: &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    key.get(..len)
+        .ok_or_else(|| format!("{} needs a key of at least {} bytes", name, len))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_short_keys() {
+        for name in ["dnac", "dnac-ctr", "aes128", "aes256", "toy"] {
+            assert!(cipher_by_name(name, &[0; 32], 1).is_ok());
+            assert!(cipher_by_name(name, &[0; 12], 1).is_err());
+        }
+        assert!(cipher_by_name("aes256", &[0; 16], 1).is_err());
+        assert!(cipher_by_name("dnac-sponge", &[0; 12], 1).is_ok());
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{stats::TestResult, Block, BlockCipherUnderTest};
+use crate::bits::BitsAll;
+
+const N: u32 = 12;
+const T: usize = 2; // 16 bits = 2 u8
+const BOUNDS: [usize; 4] = [117, 123, 129, 135];
+const PROBABILITIES: [f64; 5] = [0.206246, 0.194005, 0.219834, 0.183968, 0.195947];
+
+/// Number of collisions on the first 16 output bits among encryptions of all
+/// 2^12 variants of `input` with changed first 12 bits.
+pub fn collisions<C>(cipher: &C, input: Block) -> usize
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    let mut outputs = HashSet::<[u8; T]>::new();
+    let mut collisions = 0;
+    for bits in BitsAll::new(input, N) {
+        let output: [u8; T] = cipher.encrypt_block(bits)[..T].try_into().unwrap();
+        if !outputs.insert(output) {
+            collisions += 1;
+        }
+    }
+    collisions
+}
+
+/// Collision test: numbers of collisions for each text should follow the
+/// distribution expected for a random function.
+pub fn collision_test<C>(cipher: &C, texts: &[Block]) -> TestResult
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    let bins = texts
+        .par_iter()
+        .map(|&input| {
+            let collisions = collisions(cipher, input);
+            let mut bins = vec![0; 5];
+            bins[BOUNDS.iter().take_while(|&&b| collisions >= b).count()] += 1;
+            bins
+        })
+        .reduce(
+            || vec![0; 5],
+            |mut acc, x| {
+                for (a, b) in acc.iter_mut().zip(x) {
+                    *a += b;
+                }
+                acc
+            },
+        );
+    TestResult::chi_square("collision", bins, &PROBABILITIES)
+}
@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{stats::TestResult, Block, BlockCipherUnderTest};
+use crate::bits::BitsAll;
+
+const N: u32 = 12;
+const T: usize = 2; // 12 bits < 2 u8
+const BOUNDS: [usize; 4] = [2573, 2585, 2595, 2607];
+const PROBABILITIES: [f64; 5] = [0.199176, 0.204681, 0.197862, 0.203232, 0.195049];
+
+/// Number of distinct values of the last 12 bits of the first two output bytes among
+/// encryptions of all 2^12 variants of `input` with changed first 12 bits.
+pub fn coverage<C>(cipher: &C, input: Block) -> usize
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    let mut outputs = HashSet::<[u8; T]>::new();
+    for bits in BitsAll::new(input, N) {
+        let mut output: [u8; T] = cipher.encrypt_block(bits)[..T].try_into().unwrap();
+        output[0] &= 0b0000_1111; // clear first 4 bits to compare only 12
+        outputs.insert(output);
+    }
+    outputs.len()
+}
+
+/// Coverage test: numbers of distinct outputs for each text should follow the
+/// distribution expected for a random function.
+pub fn coverage_test<C>(cipher: &C, texts: &[Block]) -> TestResult
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    let bins = texts
+        .par_iter()
+        .map(|&input| {
+            let coverage = coverage(cipher, input);
+            let mut bins = vec![0; 5];
+            bins[BOUNDS.iter().take_while(|&&b| coverage >= b).count()] += 1;
+            bins
+        })
+        .reduce(
+            || vec![0; 5],
+            |mut acc, x| {
+                for (a, b) in acc.iter_mut().zip(x) {
+                    *a += b;
+                }
+                acc
+            },
+        );
+    TestResult::chi_square("coverage", bins, &PROBABILITIES)
+}
@@ -0,0 +1,311 @@
+use rand::Rng;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use super::{
+    random_dna,
+    stats::{normal_cdf, TestResult},
+};
+use crate::{
+    dna::DNA, round_in_place, sbox::SBox, INTRON_SIZE, KEY_SIZE, SOURCE_SIZE, TARGET_SIZE,
+};
+
+// 4^12 states, permutation of u32 takes 64 MiB
+pub const MAX_BLOCK_SIZE: usize = 12;
+// limits of E[L/n] and E[(L/n)^2] for the longest cycle L of a random permutation (Shepp, Lloyd)
+const GOLOMB_DICKMAN: f64 = 0.624_329_988_5;
+const LONGEST_SECOND_MOMENT: f64 = 0.424_750_9;
+
+/// Round function of DNAC with scaled-down block sizes, the target block can be at most
+/// as long as in the original cipher. Round keys have `target_size` bases xored into the
+/// target, 2 bases selecting the xor table and `patterns` 2-base intron patterns, so
+/// [`Variant::full`] is the original cipher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Variant {
+    source_size: usize,
+    target_size: usize,
+    intron_size: usize,
+    patterns: usize,
+}
+
+impl Variant {
+    pub fn new(
+        source_size: usize,
+        target_size: usize,
+        intron_size: usize,
+        patterns: usize,
+    ) -> Result<Self, String> {
+        if source_size < 2 {
+            return Err("source block must have at least 2 bases".to_string());
+        }
+        if target_size == 0 || !target_size.is_multiple_of(4) {
+            return Err("target block size must be a positive multiple of 4".to_string());
+        }
+        if target_size > TARGET_SIZE {
+            return Err(format!(
+                "target block must have at most {} bases",
+                TARGET_SIZE
+            ));
+        }
+        if intron_size == 0 || patterns == 0 {
+            return Err("intron size and number of patterns must be positive".to_string());
+        }
+        Ok(Variant {
+            source_size,
+            target_size,
+            intron_size,
+            patterns,
+        })
+    }
+
+    pub fn full() -> Self {
+        Variant {
+            source_size: SOURCE_SIZE,
+            target_size: TARGET_SIZE,
+            intron_size: INTRON_SIZE,
+            patterns: (KEY_SIZE - TARGET_SIZE - 2) / 2,
+        }
+    }
+
+    pub fn source_size(&self) -> usize {
+        self.source_size
+    }
+
+    pub fn target_size(&self) -> usize {
+        self.target_size
+    }
+
+    pub fn intron_size(&self) -> usize {
+        self.intron_size
+    }
+
+    pub fn patterns(&self) -> usize {
+        self.patterns
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.source_size + self.target_size
+    }
+
+    pub fn key_size(&self) -> usize {
+        self.target_size + 2 + 2 * self.patterns
+    }
+
+    pub fn random_round_keys<R: Rng>(&self, rng: &mut R, rounds: usize) -> Vec<Vec<DNA>> {
+        (0..rounds)
+            .map(|_| {
+                let mut key = random_dna(rng, self.key_size().div_ceil(4));
+                key.truncate(self.key_size());
+                key
+            })
+            .collect()
+    }
+
+    fn round(&self, input: &[DNA], key: &[DNA]) -> Vec<DNA> {
+        let mut result = input.to_vec();
+        round_in_place(
+            &SBox::new(),
+            &mut result,
+            key,
+            self.source_size,
+            self.intron_size,
+            None,
+        );
+        result
+    }
+
+    /// Encrypts a single block, with swaps of the blocks after all but the last round.
+    pub fn encrypt(&self, input: &[DNA], round_keys: &[Vec<DNA>]) -> Vec<DNA> {
+        let mut state = input.to_vec();
+        for (round, key) in round_keys.iter().enumerate() {
+            state = self.round(&state, key);
+            if round + 1 < round_keys.len() {
+                state.rotate_left(self.source_size);
+            }
+        }
+        state
+    }
+
+    /// Whole permutation of `4^block_size` states, with blocks numbered by
+    /// their 2-bit base encoding.
+    pub fn permutation(&self, round_keys: &[Vec<DNA>]) -> Result<Vec<u32>, String> {
+        let size = self.block_size();
+        if size > MAX_BLOCK_SIZE {
+            return Err(format!(
+                "block of {} bases is too large, at most {} bases can be enumerated",
+                size, MAX_BLOCK_SIZE
+            ));
+        }
+        Ok((0..1u32 << (2 * size))
+            .into_par_iter()
+            .map(|x| to_index(&self.encrypt(&to_state(x, size), round_keys)))
+            .collect())
+    }
+}
+
+fn to_state(index: u32, size: usize) -> Vec<DNA> {
+    (0..size)
+        .rev()
+        .map(|i| match (index >> (2 * i)) & 0b11 {
+            0b00 => DNA::A,
+            0b01 => DNA::G,
+            0b10 => DNA::C,
+            _ => DNA::T,
+        })
+        .collect()
+}
+
+fn to_index(state: &[DNA]) -> u32 {
+    state.iter().fold(0, |index, base| {
+        index << 2
+            | match base {
+                DNA::A => 0b00,
+                DNA::G => 0b01,
+                DNA::C => 0b10,
+                DNA::T => 0b11,
+            }
+    })
+}
+
+/// Lengths of all cycles of a permutation, longest first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleStats {
+    pub states: usize,
+    pub lengths: Vec<usize>,
+}
+
+impl CycleStats {
+    pub fn cycles(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn fixed_points(&self) -> usize {
+        self.lengths.iter().filter(|&&l| l == 1).count()
+    }
+
+    pub fn longest(&self) -> usize {
+        self.lengths.first().copied().unwrap_or(0)
+    }
+}
+
+pub fn cycle_structure(permutation: &[u32]) -> Result<CycleStats, String> {
+    let mut seen = vec![false; permutation.len()];
+    for &y in permutation {
+        match seen.get_mut(y as usize) {
+            Some(s) if !*s => *s = true,
+            _ => {
+                return Err(format!(
+                    "not a permutation, {} is repeated or out of range",
+                    y
+                ))
+            }
+        }
+    }
+
+    let mut visited = vec![false; permutation.len()];
+    let mut lengths = Vec::new();
+    for start in 0..permutation.len() {
+        let mut length = 0;
+        let mut x = start;
+        while !visited[x] {
+            visited[x] = true;
+            x = permutation[x] as usize;
+            length += 1;
+        }
+        if length > 0 {
+            lengths.push(length);
+        }
+    }
+    lengths.sort_by(|a, b| b.cmp(a));
+    Ok(CycleStats {
+        states: permutation.len(),
+        lengths,
+    })
+}
+
+/// Mean and variance of the number of cycles of a random permutation of `n` elements.
+pub fn random_cycles(n: usize) -> (f64, f64) {
+    let (h1, h2) = (1..=n).fold((0.0, 0.0), |(h1, h2), k| {
+        (h1 + 1.0 / k as f64, h2 + 1.0 / (k * k) as f64)
+    });
+    (h1, h1 - h2)
+}
+
+/// Mean and variance of the relative length of the longest cycle of a large random permutation.
+pub fn random_longest() -> (f64, f64) {
+    (
+        GOLOMB_DICKMAN,
+        LONGEST_SECOND_MOMENT - GOLOMB_DICKMAN * GOLOMB_DICKMAN,
+    )
+}
+
+fn z_test(name: &'static str, values: &[f64], mean: f64, variance: f64) -> TestResult {
+    let observed = values.iter().sum::<f64>() / values.len() as f64;
+    let z = (observed - mean) / (variance / values.len() as f64).sqrt();
+    TestResult::new(name, z, 2.0 * (1.0 - normal_cdf(z.abs())), Vec::new())
+}
+
+/// Compares cycle structures of permutations for independent keys with
+/// those of random permutations: number of cycles and relative length of the
+/// longest cycle with z-tests, numbers of fixed points against Poisson(1) with
+/// a chi-square test. There are no results without any permutations.
+pub fn compare_random(stats: &[CycleStats]) -> Vec<TestResult> {
+    let Some(first) = stats.first() else {
+        return Vec::new();
+    };
+    let (cycles_mean, cycles_variance) = random_cycles(first.states);
+    let cycles = stats
+        .iter()
+        .map(|s| s.cycles() as f64)
+        .collect::<Vec<f64>>();
+
+    let (longest_mean, longest_variance) = random_longest();
+    let longest = stats
+        .iter()
+        .map(|s| s.longest() as f64 / s.states as f64)
+        .collect::<Vec<f64>>();
+
+    let mut fixed_points = vec![0; 4];
+    for s in stats {
+        fixed_points[s.fixed_points().min(3)] += 1;
+    }
+    let e = (-1.0f64).exp();
+    let poisson = [e, e, e / 2.0, 1.0 - 2.5 * e];
+
+    vec![
+        z_test("cycles", &cycles, cycles_mean, cycles_variance),
+        TestResult::chi_square("fixed points", fixed_points, &poisson),
+        z_test("longest cycle", &longest, longest_mean, longest_variance),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DNAC;
+
+    #[test]
+    fn test_full_variant_matches_cipher() {
+        let variant = Variant::full();
+        let mut rng = rand::thread_rng();
+        let keys = variant.random_round_keys(&mut rng, 4);
+        let cipher =
+            DNAC::from_round_keys(keys.iter().map(|k| k[..].try_into().unwrap()).collect());
+        let input = random_dna(&mut rng, 16);
+        assert_eq!(variant.encrypt(&input, &keys), cipher.encrypt(input));
+    }
+
+    #[test]
+    fn test_cycle_structure() {
+        let stats = cycle_structure(&[1, 0, 2, 4, 5, 3]).unwrap();
+        assert_eq!(stats.lengths, vec![3, 2, 1]);
+        assert_eq!(stats.fixed_points(), 1);
+        assert!(cycle_structure(&[1, 1, 0]).is_err());
+        assert!(Variant::new(4, TARGET_SIZE + 4, 4, 1).is_err());
+
+        let variant = Variant::new(4, 4, 4, 1).unwrap();
+        let keys = variant.random_round_keys(&mut rand::thread_rng(), 3);
+        let stats = cycle_structure(&variant.permutation(&keys).unwrap()).unwrap();
+        assert_eq!(stats.lengths.iter().sum::<usize>(), 1 << 16);
+        assert!(compare_random(&[]).is_empty());
+    }
+}
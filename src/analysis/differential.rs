@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    dna::{xors::dnaxor_1, DNA},
+    DNAC, INPUT_SIZE, KEY_SIZE,
+};
+
+// Differences are computed with dnaxor_1, which is the bitwise xor of the 2-bit
+// encoding used by binary_to_DNA, so a difference given in bytes and in bases
+// describes the same pairs of plaintexts.
+pub type State = [DNA; INPUT_SIZE];
+
+pub fn difference(a: &State, b: &State) -> State {
+    let mut result = [DNA::A; INPUT_SIZE];
+    for (r, (&a, &b)) in result.iter_mut().zip(a.iter().zip(b)) {
+        *r = dnaxor_1(a, b);
+    }
+    result
+}
+
+pub fn random_state<R: Rng>(rng: &mut R) -> State {
+    let mut state = [DNA::A; INPUT_SIZE];
+    for base in &mut state {
+        *base = random_base(rng);
+    }
+    state
+}
+
+fn random_base<R: Rng>(rng: &mut R) -> DNA {
+    match rng.gen_range(0..4) {
+        0 => DNA::A,
+        1 => DNA::C,
+        2 => DNA::G,
+        _ => DNA::T,
+    }
+}
+
+/// Frequencies of output differences for a fixed input difference.
+#[derive(Clone, Debug)]
+pub struct DifferenceTable {
+    pub input: State,
+    pub pairs: usize,
+    pub counts: HashMap<State, usize>,
+}
+
+impl DifferenceTable {
+    fn from_differences(input: State, differences: Vec<State>) -> Self {
+        let pairs = differences.len();
+        let mut counts = HashMap::new();
+        for d in differences {
+            *counts.entry(d).or_insert(0) += 1;
+        }
+        DifferenceTable {
+            input,
+            pairs,
+            counts,
+        }
+    }
+
+    /// `n` most frequent output differences with their counts.
+    pub fn top(&self, n: usize) -> Vec<(State, usize)> {
+        let mut counts = self
+            .counts
+            .iter()
+            .map(|(&d, &c)| (d, c))
+            .collect::<Vec<(State, usize)>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(n);
+        counts
+    }
+
+    pub fn probability(&self, count: usize) -> f64 {
+        count as f64 / self.pairs as f64
+    }
+
+    /// Histogram of the number of bases which differ in the output pairs.
+    pub fn active_bases(&self) -> Vec<usize> {
+        let mut histogram = vec![0; INPUT_SIZE + 1];
+        for (d, c) in &self.counts {
+            histogram[d.iter().filter(|&&b| b != DNA::A).count()] += c;
+        }
+        histogram
+    }
+}
+
+/// Encrypts `pairs` random plaintext pairs with difference `input` and tabulates
+/// differences of the ciphertexts.
+pub fn output_differences<R: Rng>(
+    cipher: &DNAC,
+    input: &State,
+    pairs: usize,
+    rng: &mut R,
+) -> DifferenceTable {
+    let texts = (0..pairs)
+        .map(|_| random_state(rng))
+        .collect::<Vec<State>>();
+    let differences = texts
+        .into_par_iter()
+        .map(|text| {
+            let other = difference(&text, input);
+            let a = cipher.encrypt(text.to_vec());
+            let b = cipher.encrypt(other.to_vec());
+            difference(&a.try_into().unwrap(), &b.try_into().unwrap())
+        })
+        .collect();
+    DifferenceTable::from_differences(*input, differences)
+}
+
+/// Output differences after a single round averaged over random round keys.
+/// Blocks are swapped after the round unless it is the `last` round of encryption.
+pub fn round_differences<R: Rng>(
+    input: &State,
+    pairs: usize,
+    last: bool,
+    rng: &mut R,
+) -> DifferenceTable {
+    let samples = (0..pairs)
+        .map(|_| {
+            let mut key = [DNA::A; KEY_SIZE];
+            for base in &mut key {
+                *base = random_base(rng);
+            }
+            (random_state(rng), key)
+        })
+        .collect::<Vec<(State, [DNA; KEY_SIZE])>>();
+    let cipher = DNAC::from_round_keys(Vec::new());
+    let differences = samples
+        .into_par_iter()
+        .map(|(text, key)| {
+            let other = difference(&text, input);
+            difference(
+                &cipher.feistel_round(&text, &key, last),
+                &cipher.feistel_round(&other, &key, last),
+            )
+        })
+        .collect();
+    DifferenceTable::from_differences(*input, differences)
+}
+
+/// Sequence of differences after each round with estimated probability.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Characteristic {
+    // differences[0] is the input difference, differences[r] the difference after r rounds
+    pub differences: Vec<State>,
+    pub probability: f64,
+}
+
+impl Characteristic {
+    pub fn rounds(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    pub fn log2_probability(&self) -> f64 {
+        self.probability.log2()
+    }
+}
+
+/// Beam search for high probability characteristics starting with `input`.
+/// Every round keeps `beam` best characteristics, probabilities of single
+/// rounds are estimated from `pairs` samples. Returns the best characteristic
+/// found for each number of rounds from 1 to `rounds`, fewer if no characteristic
+/// is left after some round.
+pub fn search_characteristics<R: Rng>(
+    input: &State,
+    rounds: usize,
+    pairs: usize,
+    beam: usize,
+    rng: &mut R,
+) -> Vec<Characteristic> {
+    let mut candidates = vec![Characteristic {
+        differences: vec![*input],
+        probability: 1.0,
+    }];
+    let mut best = Vec::new();
+    for round in 0..rounds {
+        let mut extended: HashMap<State, Characteristic> = HashMap::new();
+        for candidate in &candidates {
+            let table = round_differences(
+                candidate.differences.last().unwrap(),
+                pairs,
+                round == rounds - 1,
+                rng,
+            );
+            for (d, count) in table.top(beam) {
+                let probability = candidate.probability * table.probability(count);
+                if extended.get(&d).is_none_or(|c| c.probability < probability) {
+                    let mut differences = candidate.differences.clone();
+                    differences.push(d);
+                    extended.insert(
+                        d,
+                        Characteristic {
+                            differences,
+                            probability,
+                        },
+                    );
+                }
+            }
+        }
+        candidates = extended.into_values().collect();
+        candidates.sort_by(|a, b| {
+            b.probability
+                .total_cmp(&a.probability)
+                .then_with(|| a.differences.cmp(&b.differences))
+        });
+        candidates.truncate(beam);
+        match candidates.first() {
+            Some(characteristic) => best.push(characteristic.clone()),
+            // no pairs were sampled or the beam is empty
+            None => break,
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_target_difference_passes_round() {
+        // difference only in the target block does not affect the round function
+        let mut input = [DNA::A; INPUT_SIZE];
+        input[INPUT_SIZE - 1] = DNA::C;
+        let table = round_differences(&input, 64, false, &mut rand::thread_rng());
+        assert_eq!(table.top(1)[0].1, 64);
+        // the last round keeps the difference at the end of the target block
+        let table = round_differences(&input, 64, true, &mut rand::thread_rng());
+        assert_eq!(table.top(1)[0], (input, 64));
+
+        let best = search_characteristics(&input, 2, 64, 4, &mut rand::thread_rng());
+        assert_eq!(best.len(), 2);
+        assert_eq!(best[0].probability, 1.0);
+        assert!(search_characteristics(&input, 2, 64, 0, &mut rand::thread_rng()).is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use rand::Rng;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    analysis::random_dna,
+    dna::{xors::dnaxor_1, DNA},
+    trace::RoundTrace,
+    DNAC, INPUT_SIZE,
+};
+
+/// Fractions of differing bits and bases between the states of two random permutations.
+pub const RANDOM_BITS: f64 = 0.5;
+pub const RANDOM_BASES: f64 = 0.75;
+
+/// Input of the cipher which is changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Key,
+    Plaintext,
+}
+
+/// Single change of the input: one bit of the binary encoding, or one base
+/// replaced with a random different base.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flip {
+    Bit,
+    Base,
+}
+
+/// Mean fractions of bits and bases which differ between the states after `rounds` rounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffusionPoint {
+    pub rounds: usize,
+    pub bits: f64,
+    pub bases: f64,
+}
+
+// number of differing bits of the binary encoding of bases with difference `d`
+fn bit_weight(d: DNA) -> usize {
+    match d {
+        DNA::A => 0,
+        DNA::C | DNA::G => 1,
+        DNA::T => 2,
+    }
+}
+
+fn flip<R: Rng>(rng: &mut R, input: &[DNA], flip: Flip) -> Vec<DNA> {
+    let mut flipped = input.to_vec();
+    let position = rng.gen_range(0..input.len());
+    let difference = match flip {
+        Flip::Bit => [DNA::G, DNA::C][rng.gen_range(0..2)],
+        Flip::Base => [DNA::C, DNA::G, DNA::T][rng.gen_range(0..3)],
+    };
+    flipped[position] = dnaxor_1(flipped[position], difference);
+    flipped
+}
+
+// whole state (source and target halves) after every round
+fn round_states(cipher: &DNAC, text: Vec<DNA>) -> Vec<Vec<DNA>> {
+    let mut states = Vec::new();
+    cipher.encrypt_observed(text, &mut |trace: RoundTrace| {
+        states.push([trace.source, trace.output].concat());
+    });
+    states
+}
+
+/// Diffusion curve of `rounds` rounds of DNAC: distances between the states after each
+/// round for `samples` random keys and plaintexts and their copies with a single change.
+/// Keys have `key_size` bytes, which must be accepted by [`DNAC::try_new`].
+pub fn diffusion_curve<R: Rng>(
+    rng: &mut R,
+    samples: usize,
+    key_size: usize,
+    rounds: usize,
+    target: Target,
+    change: Flip,
+) -> Result<Vec<DiffusionPoint>, String> {
+    // the second cipher and plaintext, only one of them is changed
+    let inputs = (0..samples)
+        .map(|_| {
+            let key = random_dna(rng, key_size);
+            let text = random_dna(rng, INPUT_SIZE / 4);
+            let (other_key, other_text) = match target {
+                Target::Key => (flip(rng, &key, change), text.clone()),
+                Target::Plaintext => (key.clone(), flip(rng, &text, change)),
+            };
+            Ok((
+                DNAC::try_new(key, rounds)?,
+                text,
+                DNAC::try_new(other_key, rounds)?,
+                other_text,
+            ))
+        })
+        .collect::<Result<Vec<(DNAC, Vec<DNA>, DNAC, Vec<DNA>)>, String>>()?;
+
+    // [round] -> (differing bits, differing bases), summed over samples
+    let distances = inputs
+        .into_par_iter()
+        .map(|(cipher, text, other_cipher, other_text)| {
+            let states = round_states(&cipher, text);
+            let other_states = round_states(&other_cipher, other_text);
+            states
+                .iter()
+                .zip(other_states)
+                .map(|(a, b)| {
+                    let d = a.iter().zip(&b).map(|(&a, &b)| dnaxor_1(a, b));
+                    (
+                        d.clone().map(bit_weight).sum::<usize>(),
+                        d.filter(|&d| d != DNA::A).count(),
+                    )
+                })
+                .collect::<Vec<(usize, usize)>>()
+        })
+        .reduce(
+            || vec![(0, 0); rounds],
+            |mut acc, x| {
+                for (acc, x) in acc.iter_mut().zip(x) {
+                    acc.0 += x.0;
+                    acc.1 += x.1;
+                }
+                acc
+            },
+        );
+    Ok(distances
+        .iter()
+        .enumerate()
+        .map(|(round, &(bits, bases))| DiffusionPoint {
+            rounds: round + 1,
+            bits: bits as f64 / (samples * INPUT_SIZE * 2) as f64,
+            bases: bases as f64 / (samples * INPUT_SIZE) as f64,
+        })
+        .collect())
+}
+
+/// Smallest number of rounds after which both distances stay within 3 standard errors
+/// of the values expected for a random permutation, `None` if it is not reached.
+pub fn full_avalanche(curve: &[DiffusionPoint], samples: usize) -> Option<usize> {
+    let bits_error =
+        3.0 * (RANDOM_BITS * (1.0 - RANDOM_BITS) / (samples * INPUT_SIZE * 2) as f64).sqrt();
+    let bases_error =
+        3.0 * (RANDOM_BASES * (1.0 - RANDOM_BASES) / (samples * INPUT_SIZE) as f64).sqrt();
+    let random = |p: &DiffusionPoint| {
+        (p.bits - RANDOM_BITS).abs() <= bits_error && (p.bases - RANDOM_BASES).abs() <= bases_error
+    };
+    let first = curve.len() - curve.iter().rev().take_while(|p| random(p)).count();
+    curve.get(first).map(|p| p.rounds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diffusion_curve() {
+        let mut rng = rand::thread_rng();
+        let curve = diffusion_curve(&mut rng, 64, 16, 12, Target::Plaintext, Flip::Bit).unwrap();
+        assert_eq!(curve.len(), 12);
+        // a single round changes at most the target half and the flipped base
+        assert!(curve[0].bases <= 21.0 / 64.0);
+        assert!((curve[11].bits - RANDOM_BITS).abs() < 0.05);
+        assert!((curve[11].bases - RANDOM_BASES).abs() < 0.05);
+        assert!(full_avalanche(&curve[..1], 64).is_none());
+        assert!(diffusion_curve(&mut rng, 64, 12, 12, Target::Key, Flip::Bit).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use rand::Rng;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use super::random_dna;
+use crate::{dna::DNA, trace::RoundTrace, DNAC, SOURCE_SIZE, TARGET_SIZE};
+
+// round function stops looking for introns after 5 matched patterns
+const MAX_MATCHES: usize = 5;
+
+/// Distributions of intron lengths, numbers of matched patterns and matched positions
+/// collected for every round over random keys and plaintexts.
+#[derive(Clone, Debug)]
+pub struct IntronStats {
+    pub rounds: usize,
+    // [round][intron_len] -> count, intron_len in 0..=TARGET_SIZE
+    pub intron_len: Vec<[usize; TARGET_SIZE + 1]>,
+    // [round][number of matched patterns] -> count
+    pub matches: Vec<[usize; MAX_MATCHES + 1]>,
+    // [round][position in source block] -> count
+    pub positions: Vec<[usize; SOURCE_SIZE]>,
+    pub keys: Vec<KeyIntronStats>,
+}
+
+/// Intron lengths observed for a single key.
+#[derive(Clone, Debug)]
+pub struct KeyIntronStats {
+    pub key: Vec<DNA>,
+    // mean intron_len in each round
+    pub mean_intron_len: Vec<f64>,
+}
+
+impl KeyIntronStats {
+    pub fn mean(&self) -> f64 {
+        self.mean_intron_len.iter().sum::<f64>() / self.mean_intron_len.len() as f64
+    }
+}
+
+impl IntronStats {
+    fn empty(rounds: usize) -> Self {
+        IntronStats {
+            rounds,
+            intron_len: vec![[0; TARGET_SIZE + 1]; rounds],
+            matches: vec![[0; MAX_MATCHES + 1]; rounds],
+            positions: vec![[0; SOURCE_SIZE]; rounds],
+            keys: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, trace: &RoundTrace) {
+        let round = trace.round;
+        self.intron_len[round][trace.intron_len()] += 1;
+        self.matches[round][trace.introns.len()] += 1;
+        for (pos, _) in &trace.introns {
+            self.positions[round][*pos] += 1;
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for r in 0..self.rounds {
+            for (a, b) in self.intron_len[r].iter_mut().zip(other.intron_len[r]) {
+                *a += b;
+            }
+            for (a, b) in self.matches[r].iter_mut().zip(other.matches[r]) {
+                *a += b;
+            }
+            for (a, b) in self.positions[r].iter_mut().zip(other.positions[r]) {
+                *a += b;
+            }
+        }
+        self.keys.extend(other.keys);
+        self
+    }
+
+    pub fn mean_intron_len(&self, round: usize) -> f64 {
+        mean(&self.intron_len[round])
+    }
+
+    pub fn mean_matches(&self, round: usize) -> f64 {
+        mean(&self.matches[round])
+    }
+
+    /// Fraction of blocks in which no intron was extracted in given round.
+    pub fn no_intron_rate(&self, round: usize) -> f64 {
+        self.intron_len[round][0] as f64 / self.intron_len[round].iter().sum::<usize>() as f64
+    }
+
+    /// Keys whose mean intron length is more than `z` standard deviations
+    /// below the mean over all keys - candidates for weak keys.
+    pub fn weak_key_candidates(&self, z: f64) -> Vec<&KeyIntronStats> {
+        let means = self.keys.iter().map(|k| k.mean()).collect::<Vec<f64>>();
+        let n = means.len() as f64;
+        let mu = means.iter().sum::<f64>() / n;
+        let sigma = (means.iter().map(|m| (m - mu).powi(2)).sum::<f64>() / n).sqrt();
+        self.keys
+            .iter()
+            .zip(means)
+            .filter(|(_, m)| *m < mu - z * sigma)
+            .map(|(k, _)| k)
+            .collect()
+    }
+}
+
+fn mean(histogram: &[usize]) -> f64 {
+    let total = histogram.iter().sum::<usize>() as f64;
+    histogram
+        .iter()
+        .enumerate()
+        .map(|(value, &count)| (value * count) as f64)
+        .sum::<f64>()
+        / total
+}
+
+/// Encrypts `n_texts` random one-block plaintexts with each of `n_keys` random keys
+/// of `key_size` bytes and collects intron statistics for all rounds.
+pub fn intron_statistics<R: Rng>(
+    rng: &mut R,
+    n_keys: usize,
+    n_texts: usize,
+    key_size: usize,
+    rounds: usize,
+) -> Result<IntronStats, String> {
+    let inputs = (0..n_keys)
+        .map(|_| {
+            let key = random_dna(rng, key_size);
+            let cipher = DNAC::try_new(key.clone(), rounds)?;
+            let texts = (0..n_texts)
+                .map(|_| random_dna(rng, 16))
+                .collect::<Vec<Vec<DNA>>>();
+            Ok((key, cipher, texts))
+        })
+        .collect::<Result<Vec<(Vec<DNA>, DNAC, Vec<Vec<DNA>>)>, String>>()?;
+
+    Ok(inputs
+        .into_par_iter()
+        .map(|(key, cipher, texts)| {
+            let mut stats = IntronStats::empty(rounds);
+            let mut key_len = vec![0; rounds];
+            for text in texts {
+                cipher.encrypt_observed(text, &mut |trace: RoundTrace| {
+                    key_len[trace.round] += trace.intron_len();
+                    stats.add(&trace);
+                });
+            }
+            stats.keys.push(KeyIntronStats {
+                key,
+                mean_intron_len: key_len.iter().map(|&l| l as f64 / n_texts as f64).collect(),
+            });
+            stats
+        })
+        .reduce(|| IntronStats::empty(rounds), IntronStats::merge))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intron_statistics() {
+        let stats = intron_statistics(&mut rand::thread_rng(), 8, 16, 16, 4).unwrap();
+        assert_eq!(stats.keys.len(), 8);
+        for round in 0..4 {
+            assert_eq!(stats.intron_len[round].iter().sum::<usize>(), 8 * 16);
+            assert_eq!(stats.matches[round].iter().sum::<usize>(), 8 * 16);
+            assert!(stats.mean_intron_len(round) <= TARGET_SIZE as f64);
+        }
+        assert!(intron_statistics(&mut rand::thread_rng(), 8, 16, 10, 4).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use rand::Rng;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    dna::{binary_to_DNA, DNA},
+    key_schedule::KeySchedule,
+    KEY_SIZE,
+};
+
+pub type RoundKey = [DNA; KEY_SIZE];
+
+pub fn round_keys(schedule: &dyn KeySchedule, key: &[u8], rounds: usize) -> Vec<RoundKey> {
+    let key = key.iter().flat_map(binary_to_DNA).collect::<Vec<DNA>>();
+    schedule.expand(&key, rounds)
+}
+
+/// Number of bases which differ between two round keys.
+pub fn distance(a: &RoundKey, b: &RoundKey) -> usize {
+    a.iter().zip(b).filter(|(a, b)| a != b).count()
+}
+
+/// Pair of round keys closer to each other than expected for random keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlidPair {
+    pub first: usize,
+    pub second: usize,
+    pub distance: usize,
+}
+
+/// All pairs of round keys which differ in at most `max_distance` bases
+/// (random round keys differ in 21 of 28 bases on average).
+pub fn slid_pairs(round_keys: &[RoundKey], max_distance: usize) -> Vec<SlidPair> {
+    (0..round_keys.len())
+        .flat_map(|i| (i + 1..round_keys.len()).map(move |j| (i, j)))
+        .map(|(first, second)| SlidPair {
+            first,
+            second,
+            distance: distance(&round_keys[first], &round_keys[second]),
+        })
+        .filter(|pair| pair.distance <= max_distance)
+        .collect()
+}
+
+/// Fraction of equal bases between round keys `shift` rounds apart, for every
+/// shift from 1 to `round_keys.len() - 1`; 0.25 is expected for random keys.
+pub fn shift_similarity(round_keys: &[RoundKey]) -> Vec<f64> {
+    (1..round_keys.len())
+        .map(|shift| {
+            let pairs = round_keys.len() - shift;
+            let equal = (0..pairs)
+                .map(|i| KEY_SIZE - distance(&round_keys[i], &round_keys[i + shift]))
+                .sum::<usize>();
+            equal as f64 / (pairs * KEY_SIZE) as f64
+        })
+        .collect()
+}
+
+/// Smallest period of the round key sequence, if it repeats within given rounds.
+pub fn period(round_keys: &[RoundKey]) -> Option<usize> {
+    (1..round_keys.len())
+        .find(|&p| (p..round_keys.len()).all(|i| round_keys[i] == round_keys[i - p]))
+}
+
+pub fn distinct(round_keys: &[RoundKey]) -> usize {
+    let mut keys = round_keys.to_vec();
+    keys.sort();
+    keys.dedup();
+    keys.len()
+}
+
+/// Keys with simple structure, which should not lead to structured round keys.
+pub fn structured_keys(key_size: usize) -> Vec<(String, Vec<u8>)> {
+    vec![
+        ("zeros".to_string(), vec![0x00; key_size]),
+        ("ones".to_string(), vec![0xff; key_size]),
+        ("alternating".to_string(), vec![0x55; key_size]),
+        (
+            "repeated 2 bytes".to_string(),
+            (0..key_size).map(|i| [0x3c, 0xa5][i % 2]).collect(),
+        ),
+        (
+            "repeated 4 bytes".to_string(),
+            (0..key_size)
+                .map(|i| [0x01, 0x23, 0x45, 0x67][i % 4])
+                .collect(),
+        ),
+        ("counter".to_string(), (0..key_size as u8).collect()),
+        (
+            "single bit".to_string(),
+            (0..key_size)
+                .map(|i| if i == 0 { 0x80 } else { 0 })
+                .collect(),
+        ),
+    ]
+}
+
+/// For every round key, the mean and minimal (over all flipped key bits) fraction
+/// of bases changed by flipping a single bit of `n_keys` random keys; 0.75 is expected
+/// for independent round keys.
+pub fn related_key_diffusion<R: Rng>(
+    schedule: &(dyn KeySchedule + Sync),
+    rng: &mut R,
+    n_keys: usize,
+    key_size: usize,
+    rounds: usize,
+) -> Vec<(f64, f64)> {
+    let keys = (0..n_keys)
+        .map(|_| (0..key_size).map(|_| rng.gen()).collect())
+        .collect::<Vec<Vec<u8>>>();
+    let bits = key_size * 8;
+    // [flipped bit][round] -> number of changed bases, summed over keys
+    let changed = keys
+        .into_par_iter()
+        .map(|key| {
+            let original = round_keys(schedule, &key, rounds);
+            (0..bits)
+                .map(|bit| {
+                    let mut related = key.clone();
+                    related[bit / 8] ^= 0b1000_0000 >> (bit % 8);
+                    round_keys(schedule, &related, rounds)
+                        .iter()
+                        .zip(&original)
+                        .map(|(a, b)| distance(a, b))
+                        .collect::<Vec<usize>>()
+                })
+                .collect::<Vec<Vec<usize>>>()
+        })
+        .reduce(
+            || vec![vec![0; rounds]; bits],
+            |mut acc, x| {
+                for (acc, x) in acc.iter_mut().zip(x) {
+                    for (a, b) in acc.iter_mut().zip(x) {
+                        *a += b;
+                    }
+                }
+                acc
+            },
+        );
+    let total = (n_keys * KEY_SIZE) as f64;
+    (0..rounds)
+        .map(|round| {
+            let fractions = changed
+                .iter()
+                .map(|bit| bit[round] as f64 / total)
+                .collect::<Vec<f64>>();
+            let mean = fractions.iter().sum::<f64>() / bits as f64;
+            let min = fractions.iter().cloned().fold(f64::INFINITY, f64::min);
+            (mean, min)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key_schedule::AesSchedule;
+
+    #[test]
+    fn test_period() {
+        let a = [DNA::A; KEY_SIZE];
+        let c = [DNA::C; KEY_SIZE];
+        assert_eq!(period(&[a, c, a, c, a]), Some(2));
+        assert_eq!(period(&[a, c, c]), None);
+        assert_eq!(distinct(&[a, c, a]), 2);
+        assert_eq!(slid_pairs(&[a, c, a], 0).len(), 1);
+    }
+
+    #[test]
+    fn test_related_key_diffusion() {
+        let diffusion = related_key_diffusion(&AesSchedule, &mut rand::thread_rng(), 2, 16, 4);
+        assert_eq!(diffusion.len(), 4);
+        assert!(diffusion
+            .iter()
+            .all(|(mean, min)| min <= mean && *mean <= 1.0));
+    }
+}
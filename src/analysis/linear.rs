@@ -0,0 +1,180 @@
+use rand::Rng;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{random_blocks, BlockCipherUnderTest};
+use crate::{
+    dna::{binary_to_DNA, DNA_to_binary},
+    sbox::SBox,
+};
+
+pub type Mask = u128;
+
+// normal quantile for 95% confidence intervals
+const Z_95: f64 = 1.96;
+
+/// Linear approximation `<input_mask, P> = <output_mask, C>` with estimated bias.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Approximation {
+    pub input_mask: Mask,
+    pub output_mask: Mask,
+    pub samples: usize,
+    // P(approximation holds) - 1/2
+    pub bias: f64,
+}
+
+impl Approximation {
+    pub fn correlation(&self) -> f64 {
+        2.0 * self.bias
+    }
+
+    /// Half-width of the 95% confidence interval of the bias.
+    pub fn confidence(&self) -> f64 {
+        Z_95 * 0.5 / (self.samples as f64).sqrt()
+    }
+
+    pub fn interval(&self) -> (f64, f64) {
+        (self.bias - self.confidence(), self.bias + self.confidence())
+    }
+
+    /// True if zero bias is outside of the confidence interval.
+    pub fn significant(&self) -> bool {
+        self.bias.abs() > self.confidence()
+    }
+}
+
+fn parity(x: u128) -> u32 {
+    x.count_ones() & 1
+}
+
+/// Expected largest absolute bias among `candidates` approximations
+/// of a random permutation estimated from `samples` pairs.
+pub fn noise_level(candidates: usize, samples: usize) -> f64 {
+    (2.0 * (2.0 * candidates as f64).ln()).sqrt() * 0.5 / (samples as f64).sqrt()
+}
+
+/// S-box as a permutation of bytes (4 bases = 8 bits).
+pub fn sbox_table() -> [u8; 256] {
+    let sbox = SBox::new();
+    let mut table = [0; 256];
+    for (x, y) in table.iter_mut().enumerate() {
+        *y = DNA_to_binary(&sbox[&binary_to_DNA(&(x as u8))]);
+    }
+    table
+}
+
+/// Linear approximation table of the S-box: `lat[a][b]` is the number of inputs
+/// for which `<a, x> = <b, S(x)>` minus 128.
+pub fn sbox_lat() -> Vec<[i32; 256]> {
+    let table = sbox_table();
+    (0..256)
+        .map(|a| {
+            let mut row = [0; 256];
+            for (b, entry) in row.iter_mut().enumerate() {
+                *entry = (0..256)
+                    .filter(|&x| parity((a & x) as u128) == parity((b as u8 & table[x]) as u128))
+                    .count() as i32
+                    - 128;
+            }
+            row
+        })
+        .collect()
+}
+
+/// `n` S-box approximations `(a, b, bias)` with the largest absolute bias.
+pub fn best_sbox_approximations(n: usize) -> Vec<(u8, u8, f64)> {
+    let lat = sbox_lat();
+    let mut approximations = (1..256)
+        .flat_map(|a| (1..256).map(move |b| (a as u8, b as u8)))
+        .map(|(a, b)| (a, b, lat[a as usize][b as usize] as f64 / 256.0))
+        .collect::<Vec<(u8, u8, f64)>>();
+    approximations.sort_by(|x, y| y.2.abs().total_cmp(&x.2.abs()).then(x.0.cmp(&y.0)));
+    approximations.truncate(n);
+    approximations
+}
+
+/// Candidate masks built from the best S-box approximations placed on every
+/// pair of input and output bytes (4 bases each), followed by single bit masks
+/// equal on input and output, which detect bits not yet mixed by the rounds.
+pub fn candidate_masks(n: usize) -> Vec<(Mask, Mask)> {
+    best_sbox_approximations(n)
+        .into_iter()
+        .flat_map(|(a, b, _)| {
+            (0..16).flat_map(move |i| {
+                (0..16).map(move |j| ((a as u128) << (8 * (15 - i)), (b as u128) << (8 * (15 - j))))
+            })
+        })
+        .chain((0..128).map(|bit| (1 << bit, 1 << bit)))
+        .collect()
+}
+
+/// Estimates bias of every approximation in `masks` on the same `samples` random plaintexts.
+pub fn estimate_biases<C, R>(
+    cipher: &C,
+    masks: &[(Mask, Mask)],
+    samples: usize,
+    rng: &mut R,
+) -> Vec<Approximation>
+where
+    C: BlockCipherUnderTest + ?Sized,
+    R: Rng,
+{
+    let pairs = random_blocks(rng, samples)
+        .par_iter()
+        .map(|&p| {
+            (
+                u128::from_be_bytes(p),
+                u128::from_be_bytes(cipher.encrypt_block(p)),
+            )
+        })
+        .collect::<Vec<(u128, u128)>>();
+    masks
+        .par_iter()
+        .map(|&(input_mask, output_mask)| {
+            let holds = pairs
+                .iter()
+                .filter(|(p, c)| parity(p & input_mask) == parity(c & output_mask))
+                .count();
+            Approximation {
+                input_mask,
+                output_mask,
+                samples,
+                bias: holds as f64 / samples as f64 - 0.5,
+            }
+        })
+        .collect()
+}
+
+/// Approximation with the largest absolute bias among `masks`.
+pub fn best_approximation<C, R>(
+    cipher: &C,
+    masks: &[(Mask, Mask)],
+    samples: usize,
+    rng: &mut R,
+) -> Approximation
+where
+    C: BlockCipherUnderTest + ?Sized,
+    R: Rng,
+{
+    estimate_biases(cipher, masks, samples, rng)
+        .into_iter()
+        .max_by(|a, b| a.bias.abs().total_cmp(&b.bias.abs()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sbox_lat() {
+        let table = sbox_table();
+        let mut sorted = table;
+        sorted.sort();
+        assert!(sorted.iter().enumerate().all(|(i, &x)| i == x as usize));
+
+        let lat = sbox_lat();
+        assert_eq!(lat[0][0], 128);
+        // every non-zero output mask is balanced over all inputs
+        assert!((1..256).all(|b| lat[0][b] == 0));
+    }
+}
@@ -0,0 +1,89 @@
+use rand::{seq::SliceRandom, Rng};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{stats::TestResult, xor_array, Block, BlockCipherUnderTest};
+use crate::bits::{powerset, BitsOne, INPUT_SIZE_BYTES};
+
+// 2^7 inputs give a square 128x128 bit matrix
+const BASE_SIZE: usize = 7;
+const PROBABILITIES: [f64; 3] = [0.133636, 0.577576, 0.288788];
+
+/// Rank over GF(2) of a matrix with rows given as 128-bit integers.
+pub fn compute_rank(mut rows: Vec<u128>) -> usize {
+    let mut rank = 0;
+    for bit in (0..128).rev() {
+        let mask = 1u128 << bit;
+        if let Some(pivot) = (rank..rows.len()).find(|&i| rows[i] & mask != 0) {
+            rows.swap(rank, pivot);
+            let pivot_row = rows[rank];
+            for row in rows.iter_mut().skip(rank + 1) {
+                if *row & mask != 0 {
+                    *row ^= pivot_row;
+                }
+            }
+            rank += 1;
+        }
+    }
+    rank
+}
+
+/// Random set of linearly independent inputs - each is a sum of 16 distinct unit vectors.
+fn random_base<R: Rng>(rng: &mut R) -> Vec<Block> {
+    let zeroes = [0u8; INPUT_SIZE_BYTES];
+    let mut bits = BitsOne::new(zeroes).collect::<Vec<Block>>();
+    bits.shuffle(rng);
+    bits.chunks(16)
+        .map(|chunk| chunk.iter().fold(zeroes, |acc, &x| xor_array(acc, x)))
+        .take(BASE_SIZE)
+        .collect()
+}
+
+/// Linear span test: outputs for all elements of a linear span of 7 random inputs
+/// should have rank distributed as for random 128x128 bit matrices.
+pub fn linear_span_test<C, R>(cipher: &C, rng: &mut R, repetitions: usize) -> TestResult
+where
+    C: BlockCipherUnderTest + ?Sized,
+    R: Rng,
+{
+    let bases = (0..repetitions)
+        .map(|_| random_base(rng))
+        .collect::<Vec<Vec<Block>>>();
+    let zeroes = [0u8; INPUT_SIZE_BYTES];
+    let bins = bases
+        .par_iter()
+        .map(|base| {
+            let rows = powerset(base)
+                .map(|chunk| chunk.iter().fold(zeroes, |acc, &&x| xor_array(acc, x)))
+                .map(|x| u128::from_be_bytes(cipher.encrypt_block(x)))
+                .collect::<Vec<u128>>();
+            let mut bins = vec![0; 3];
+            match compute_rank(rows) {
+                rank if rank < 127 => bins[0] += 1,
+                127 => bins[1] += 1,
+                _ => bins[2] += 1,
+            }
+            bins
+        })
+        .reduce(
+            || vec![0; 3],
+            |mut acc, x| {
+                for (a, b) in acc.iter_mut().zip(x) {
+                    *a += b;
+                }
+                acc
+            },
+        );
+    TestResult::chi_square("linear span", bins, &PROBABILITIES)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compute_rank() {
+        assert_eq!(compute_rank((0..128).map(|i| 1u128 << i).collect()), 128);
+        assert_eq!(compute_rank(vec![0b011, 0b110, 0b101]), 2);
+        assert_eq!(compute_rank(vec![0; 4]), 0);
+    }
+}
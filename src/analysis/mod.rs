@@ -0,0 +1,99 @@
+use rand::Rng;
+
+use crate::{
+    bits::INPUT_SIZE_BYTES,
+    dna::{binary_to_DNA, DNA_to_binary, DNA},
+};
+
+pub mod cipher;
+pub mod collision;
+pub mod coverage;
+pub mod cycles;
+pub mod differential;
+pub mod diffusion;
+pub mod introns;
+pub mod key_schedule;
+pub mod linear;
+pub mod linspan;
+pub mod nist;
+pub mod report;
+pub mod sac;
+pub mod stats;
+pub mod timing;
+
+pub type Block = [u8; INPUT_SIZE_BYTES];
+
+pub use cipher::{BlockCipherUnderTest, BlockDNAC};
+
+pub fn random_dna<R: Rng>(rng: &mut R, size_bytes: usize) -> Vec<DNA> {
+    (0..size_bytes)
+        .flat_map(|_| binary_to_DNA(&rng.gen::<u8>()))
+        .collect()
+}
+
+pub fn random_blocks<R: Rng>(rng: &mut R, n: usize) -> Vec<Block> {
+    (0..n).map(|_| rng.gen()).collect()
+}
+
+pub fn dna_to_hex(dna: &[DNA]) -> String {
+    dna.chunks_exact(4)
+        .map(|chunk| format!("{:02x}", DNA_to_binary(chunk.try_into().unwrap())))
+        .collect()
+}
+
+pub fn xor_array(a: Block, b: Block) -> Block {
+    let mut result = a;
+    for (r, b) in result.iter_mut().zip(b) {
+        *r ^= b;
+    }
+    result
+}
+
+pub fn check_ones(a: &u8) -> [u32; 8] {
+    let mut mask = 0b1000_0000;
+    let mut result = [0; 8];
+    for r in &mut result {
+        if a & mask > 0 {
+            *r = 1;
+        }
+        mask >>= 1;
+    }
+    result
+}
+
+/// Like [`check_ones`] for the four bases of a byte: 1 for every non-zero base.
+pub fn check_bases(a: &u8) -> [u32; 4] {
+    let mut result = [0; 4];
+    for (i, r) in result.iter_mut().enumerate() {
+        if (a >> (6 - 2 * i)) & 0b11 > 0 {
+            *r = 1;
+        }
+    }
+    result
+}
+
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.is_ascii() {
+        return Err(format!("illegal hex digits in {}", hex));
+    }
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("odd number of hex digits in {}", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| format!("{}: {}", hex, e)))
+        .collect()
+}
+
+pub fn parse_bases(bases: &str) -> Result<Vec<DNA>, String> {
+    bases
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'A' => Ok(DNA::A),
+            'C' => Ok(DNA::C),
+            'G' => Ok(DNA::G),
+            'T' => Ok(DNA::T),
+            _ => Err(format!("illegal base {} in {}", c, bases)),
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+use crate::analysis::stats::igamc;
+
+// probabilities of the classes of T, as in the reference implementation
+const PROBABILITIES: [f64; 7] = [
+    0.01047, 0.03125, 0.12500, 0.50000, 0.25000, 0.06250, 0.020833,
+];
+
+/// Length of the shortest LFSR generating `bits` (Berlekamp-Massey algorithm).
+pub fn berlekamp_massey(bits: &[u8]) -> usize {
+    let n = bits.len();
+    let mut c = vec![0u8; n + 1];
+    let mut b = vec![0u8; n + 1];
+    c[0] = 1;
+    b[0] = 1;
+    let mut l = 0;
+    let mut m: isize = -1;
+    for i in 0..n {
+        let d = (1..=l).fold(bits[i], |d, j| d ^ (c[j] & bits[i - j]));
+        if d == 1 {
+            let t = c.clone();
+            let shift = (i as isize - m) as usize;
+            for j in 0..=n - shift {
+                c[j + shift] ^= b[j];
+            }
+            if l <= i / 2 {
+                l = i + 1 - l;
+                m = i as isize;
+                b = t;
+            }
+        }
+    }
+    l
+}
+
+/// Linear complexity test on blocks of `block` bits.
+pub fn linear_complexity(bits: &[u8], block: usize) -> f64 {
+    let m = block as f64;
+    let sign = if block.is_multiple_of(2) { 1.0 } else { -1.0 };
+    let mean = m / 2.0 + (9.0 - sign) / 36.0 - (m / 3.0 + 2.0 / 9.0) / 2f64.powf(m);
+    let mut counts = [0u64; 7];
+    for chunk in bits.chunks_exact(block) {
+        let t = sign * (berlekamp_massey(chunk) as f64 - mean) + 2.0 / 9.0;
+        let class = match t {
+            t if t <= -2.5 => 0,
+            t if t <= -1.5 => 1,
+            t if t <= -0.5 => 2,
+            t if t <= 0.5 => 3,
+            t if t <= 1.5 => 4,
+            t if t <= 2.5 => 5,
+            _ => 6,
+        };
+        counts[class] += 1;
+    }
+    let blocks = (bits.len() / block) as f64;
+    let chi_squared = counts
+        .iter()
+        .zip(PROBABILITIES)
+        .map(|(&v, p)| (v as f64 - blocks * p).powi(2) / (blocks * p))
+        .sum::<f64>();
+    igamc((PROBABILITIES.len() - 1) as f64 / 2.0, chi_squared / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::nist::test::bits;
+
+    #[test]
+    fn test_berlekamp_massey() {
+        assert_eq!(berlekamp_massey(&bits("1101011110001")), 4);
+        assert_eq!(berlekamp_massey(&bits("0000")), 0);
+        assert_eq!(berlekamp_massey(&bits("0001")), 4);
+    }
+}
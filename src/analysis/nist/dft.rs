@@ -0,0 +1,38 @@
+use rustfft::{num_complex::Complex, FftPlanner};
+
+use crate::analysis::stats::erfc;
+
+/// Discrete Fourier transform (spectral) test.
+pub fn dft(bits: &[u8]) -> f64 {
+    let n = bits.len();
+    let mut buffer = bits
+        .iter()
+        .map(|&b| Complex::new(2.0 * b as f64 - 1.0, 0.0))
+        .collect::<Vec<Complex<f64>>>();
+    FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+
+    let threshold = ((1.0f64 / 0.05).ln() * n as f64).sqrt();
+    let expected = 0.95 * n as f64 / 2.0;
+    let below = buffer[..n / 2]
+        .iter()
+        .filter(|x| x.norm() < threshold)
+        .count();
+    let d = (below as f64 - expected) / (n as f64 * 0.95 * 0.05 / 4.0).sqrt();
+    erfc(d.abs() / 2f64.sqrt())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_periodic_sequence_fails() {
+        let periodic = (0..4096).map(|i| (i / 8 % 2) as u8).collect::<Vec<u8>>();
+        assert!(dft(&periodic) < 0.01);
+        let random = (0..4096)
+            .map(|_| rand::thread_rng().gen_range(0..2))
+            .collect::<Vec<u8>>();
+        assert!(dft(&random) > 0.0);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::analysis::stats::{erfc, igamc};
+
+const STATES: [i64; 8] = [-4, -3, -2, -1, 1, 2, 3, 4];
+const VARIANT_STATES: [i64; 18] = [
+    -9, -8, -7, -6, -5, -4, -3, -2, -1, 1, 2, 3, 4, 5, 6, 7, 8, 9,
+];
+const MIN_CYCLES: f64 = 500.0;
+
+// random walk split into cycles between returns to zero, `None` if there are too few cycles
+fn cycles(bits: &[u8]) -> Option<Vec<Vec<i64>>> {
+    let mut cycles = vec![Vec::new()];
+    let mut sum = 0;
+    for &b in bits {
+        sum += 2 * b as i64 - 1;
+        if sum == 0 {
+            cycles.push(Vec::new());
+        } else {
+            cycles.last_mut().unwrap().push(sum);
+        }
+    }
+    if sum == 0 {
+        cycles.pop();
+    }
+    let min = MIN_CYCLES.max(0.005 * (bits.len() as f64).sqrt());
+    if (cycles.len() as f64) < min {
+        return None;
+    }
+    Some(cycles)
+}
+
+// probability of `k` visits to state `x` in a cycle, the last class is 5 or more visits
+fn visits_probability(x: i64, k: usize) -> f64 {
+    let x = x.abs() as f64;
+    let stay = 1.0 - 1.0 / (2.0 * x);
+    match k {
+        0 => stay,
+        5 => stay.powi(4) / (2.0 * x),
+        k => stay.powi(k as i32 - 1) / (4.0 * x * x),
+    }
+}
+
+/// Random excursions test, p-values for states -4..-1 and 1..4.
+pub fn random_excursions(bits: &[u8]) -> Option<Vec<f64>> {
+    let cycles = cycles(bits)?;
+    let j = cycles.len() as f64;
+    Some(
+        STATES
+            .iter()
+            .map(|&x| {
+                let mut counts = [0u64; 6];
+                for cycle in &cycles {
+                    let visits = cycle.iter().filter(|&&s| s == x).count();
+                    counts[visits.min(5)] += 1;
+                }
+                let chi_squared = counts
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &v)| {
+                        let e = j * visits_probability(x, k);
+                        (v as f64 - e).powi(2) / e
+                    })
+                    .sum::<f64>();
+                igamc(2.5, chi_squared / 2.0)
+            })
+            .collect(),
+    )
+}
+
+/// Random excursions variant test, p-values for states -9..-1 and 1..9.
+pub fn random_excursions_variant(bits: &[u8]) -> Option<Vec<f64>> {
+    let cycles = cycles(bits)?;
+    let j = cycles.len() as f64;
+    Some(
+        VARIANT_STATES
+            .iter()
+            .map(|&x| {
+                let visits = cycles.iter().flatten().filter(|&&s| s == x).count() as f64;
+                erfc((visits - j).abs() / (2.0 * j * (4.0 * x.abs() as f64 - 2.0)).sqrt())
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_visits_probabilities() {
+        for x in STATES {
+            let sum = (0..6).map(|k| visits_probability(x, k)).sum::<f64>();
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+        assert!((visits_probability(3, 3) - 0.01929012346).abs() < 1e-10);
+        assert_eq!(random_excursions(&[1, 0, 1, 0]), None);
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt::Write;
+
+use super::TestOutcome;
+use crate::analysis::stats::{igamc, ALPHA};
+
+/// Significance level of the test of uniformity of p-values.
+pub const UNIFORMITY_ALPHA: f64 = 0.0001;
+
+const LINE: &str = "------------------------------------------------------------------------------";
+const DASHES: &str =
+    "- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
+
+/// P-values of a single line of the report (one test, or one template or state
+/// of the tests giving several p-values) collected over all sequences.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportRow {
+    pub name: &'static str,
+    pub p_values: Vec<f64>,
+}
+
+impl ReportRow {
+    pub fn histogram(&self) -> [u64; 10] {
+        let mut bins = [0; 10];
+        for &p in &self.p_values {
+            bins[((p * 10.0) as usize).min(9)] += 1;
+        }
+        bins
+    }
+
+    /// P-value of the chi-square test of uniformity of the p-values.
+    pub fn uniformity(&self) -> f64 {
+        let expected = self.p_values.len() as f64 / 10.0;
+        let chi_squared = self
+            .histogram()
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        igamc(4.5, chi_squared / 2.0)
+    }
+
+    pub fn passed(&self) -> usize {
+        self.p_values.iter().filter(|&&p| p >= ALPHA).count()
+    }
+}
+
+/// Minimal number of sequences out of `sample_size` which should pass each test.
+pub fn min_pass_count(sample_size: usize) -> usize {
+    let p = 1.0 - ALPHA;
+    let s = sample_size as f64;
+    ((p - 3.0 * (p * (1.0 - p) / s).sqrt()) * s) as usize
+}
+
+/// Summary of all tests over a set of sequences in the format of `finalAnalysisReport.txt`.
+#[derive(Clone, Debug, Default)]
+pub struct FinalReport {
+    pub generator: String,
+    pub sequences: usize,
+    pub rows: Vec<ReportRow>,
+}
+
+impl FinalReport {
+    pub fn new(generator: &str) -> Self {
+        FinalReport {
+            generator: generator.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds outcomes of all tests for the next sequence. Tests which are not applicable
+    /// to the sequence get an empty line until a sequence with their p-values is added.
+    pub fn add(&mut self, outcomes: &[TestOutcome]) {
+        for outcome in outcomes {
+            let start = match self.rows.iter().position(|r| r.name == outcome.name) {
+                Some(start) => start,
+                None => {
+                    self.rows.push(ReportRow {
+                        name: outcome.name,
+                        p_values: Vec::new(),
+                    });
+                    self.rows.len() - 1
+                }
+            };
+            let Some(p_values) = &outcome.p_values else {
+                continue;
+            };
+            let lines = self.rows[start..]
+                .iter()
+                .take_while(|r| r.name == outcome.name)
+                .count();
+            for i in lines..p_values.len() {
+                self.rows.insert(
+                    start + i,
+                    ReportRow {
+                        name: outcome.name,
+                        p_values: Vec::new(),
+                    },
+                );
+            }
+            for (row, &p) in self.rows[start..].iter_mut().zip(p_values) {
+                row.p_values.push(p);
+            }
+        }
+        self.sequences += 1;
+    }
+
+    /// True if the proportion of passing sequences and uniformity of p-values are acceptable for all tests.
+    pub fn passed(&self) -> bool {
+        self.rows.iter().all(|row| {
+            row.p_values.is_empty()
+                || (row.passed() >= min_pass_count(row.p_values.len())
+                    && row.uniformity() >= UNIFORMITY_ALPHA)
+        })
+    }
+}
+
+impl std::fmt::Display for FinalReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        writeln!(out, "{}", LINE)?;
+        writeln!(
+            out,
+            "RESULTS FOR THE UNIFORMITY OF P-VALUES AND THE PROPORTION OF PASSING SEQUENCES"
+        )?;
+        writeln!(out, "{}", LINE)?;
+        writeln!(out, "   generator is <{}>", self.generator)?;
+        writeln!(out, "{}", LINE)?;
+        writeln!(
+            out,
+            " C1  C2  C3  C4  C5  C6  C7  C8  C9 C10  P-VALUE  PROPORTION  STATISTICAL TEST"
+        )?;
+        writeln!(out, "{}", LINE)?;
+        for row in &self.rows {
+            for c in row.histogram() {
+                write!(out, "{:3} ", c)?;
+            }
+            let samples = row.p_values.len();
+            if samples == 0 {
+                writeln!(out, "    ----     ------     {}", row.name)?;
+                continue;
+            }
+            let uniformity = row.uniformity();
+            let mark = if uniformity < UNIFORMITY_ALPHA {
+                '*'
+            } else {
+                ' '
+            };
+            write!(out, " {:8.6} {}  ", uniformity, mark)?;
+            let mark = if row.passed() < min_pass_count(samples) {
+                '*'
+            } else {
+                ' '
+            };
+            writeln!(
+                out,
+                "{:4}/{:<4} {}  {}",
+                row.passed(),
+                samples,
+                mark,
+                row.name
+            )?;
+        }
+        writeln!(out)?;
+        writeln!(out)?;
+        writeln!(out, "{}", DASHES)?;
+        writeln!(
+            out,
+            "The minimum pass rate for each statistical test with the exception of the"
+        )?;
+        writeln!(
+            out,
+            "random excursion (variant) test is approximately = {} for a",
+            min_pass_count(self.sequences)
+        )?;
+        writeln!(out, "sample size = {} binary sequences.", self.sequences)?;
+        if let Some(row) = self
+            .rows
+            .iter()
+            .find(|r| r.name == "RandomExcursions" && !r.p_values.is_empty())
+        {
+            writeln!(out)?;
+            writeln!(
+                out,
+                "The minimum pass rate for the random excursion (variant) test"
+            )?;
+            writeln!(
+                out,
+                "is approximately = {} for a sample size = {} binary sequences.",
+                min_pass_count(row.p_values.len()),
+                row.p_values.len()
+            )?;
+        }
+        writeln!(out, "{}", DASHES)?;
+        write!(f, "{}", out)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::analysis::stats::{erfc, igamc, normal_cdf};
+
+/// Frequency (monobit) test.
+pub fn frequency(bits: &[u8]) -> f64 {
+    let n = bits.len() as f64;
+    let sum = bits.iter().map(|&b| 2 * b as i64 - 1).sum::<i64>();
+    erfc(sum.abs() as f64 / n.sqrt() / 2f64.sqrt())
+}
+
+/// Frequency test within blocks of `block` bits.
+pub fn block_frequency(bits: &[u8], block: usize) -> f64 {
+    let blocks = bits.len() / block;
+    let chi_squared = 4.0
+        * block as f64
+        * bits
+            .chunks_exact(block)
+            .map(|chunk| {
+                let pi = chunk.iter().map(|&b| b as usize).sum::<usize>() as f64 / block as f64;
+                (pi - 0.5).powi(2)
+            })
+            .sum::<f64>();
+    igamc(blocks as f64 / 2.0, chi_squared / 2.0)
+}
+
+/// Cumulative sums test, forward and backward, for a non-empty sequence.
+pub fn cumulative_sums(bits: &[u8]) -> [f64; 2] {
+    let forward = max_excursion(bits.iter());
+    let backward = max_excursion(bits.iter().rev());
+    [
+        cumulative_sums_p_value(bits.len() as i64, forward),
+        cumulative_sums_p_value(bits.len() as i64, backward),
+    ]
+}
+
+fn max_excursion<'a>(bits: impl Iterator<Item = &'a u8>) -> i64 {
+    bits.scan(0i64, |sum, &b| {
+        *sum += 2 * b as i64 - 1;
+        Some(sum.abs())
+    })
+    .max()
+    .unwrap_or(0)
+}
+
+fn cumulative_sums_p_value(n: i64, z: i64) -> f64 {
+    let sqrt_n = (n as f64).sqrt();
+    let phi = |k: i64, shift: i64| normal_cdf(((4 * k + shift) * z) as f64 / sqrt_n);
+    // bounds use integer division as in the reference implementation
+    let sum1 = ((-n / z + 1) / 4..=(n / z - 1) / 4)
+        .map(|k| phi(k, 1) - phi(k, -1))
+        .sum::<f64>();
+    let sum2 = ((-n / z - 3) / 4..=(n / z - 1) / 4)
+        .map(|k| phi(k, 3) - phi(k, 1))
+        .sum::<f64>();
+    1.0 - sum1 + sum2
+}
+
+/// Runs test.
+pub fn runs(bits: &[u8]) -> f64 {
+    let n = bits.len() as f64;
+    let pi = bits.iter().map(|&b| b as usize).sum::<usize>() as f64 / n;
+    if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+        // frequency test prerequisite is not met
+        return 0.0;
+    }
+    let v = 1 + bits.windows(2).filter(|w| w[0] != w[1]).count();
+    erfc((v as f64 - 2.0 * n * pi * (1.0 - pi)).abs() / (2.0 * (2.0 * n).sqrt() * pi * (1.0 - pi)))
+}
+
+/// Test for the longest run of ones in a block, block size depends on the sequence length,
+/// which must be at least 128 bits.
+pub fn longest_run(bits: &[u8]) -> f64 {
+    let (block, min_run, probabilities): (usize, usize, &[f64]) = match bits.len() {
+        n if n < 6272 => (8, 1, &[0.2148, 0.3672, 0.2305, 0.1875]),
+        n if n < 750_000 => (128, 4, &[0.1174, 0.2430, 0.2493, 0.1752, 0.1027, 0.1124]),
+        _ => (
+            10_000,
+            10,
+            &[0.0882, 0.2092, 0.2483, 0.1933, 0.1208, 0.0675, 0.0727],
+        ),
+    };
+    let k = probabilities.len() - 1;
+    let mut counts = vec![0u64; k + 1];
+    for chunk in bits.chunks_exact(block) {
+        let longest = chunk
+            .split(|&b| b == 0)
+            .map(|run| run.len())
+            .max()
+            .unwrap_or(0);
+        counts[longest.clamp(min_run, min_run + k) - min_run] += 1;
+    }
+    let blocks = (bits.len() / block) as f64;
+    let chi_squared = counts
+        .iter()
+        .zip(probabilities)
+        .map(|(&v, p)| (v as f64 - blocks * p).powi(2) / (blocks * p))
+        .sum::<f64>();
+    igamc(k as f64 / 2.0, chi_squared / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::nist::test::{bits, EPSILON_100};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_sp800_22_examples() {
+        let epsilon = bits(EPSILON_100);
+        assert_close(frequency(&epsilon), 0.109599);
+        assert_close(block_frequency(&epsilon, 10), 0.706438);
+        assert_close(runs(&epsilon), 0.500798);
+        let [forward, backward] = cumulative_sums(&epsilon);
+        assert_close(forward, 0.219194);
+        assert_close(backward, 0.114866);
+
+        let epsilon = bits("11001100000101010110110001001100111000000000001001001101010100010001001111010110100000001101011111001100111001101101100010110010");
+        // the document rounds the p-value computed from chi^2 = 4.882605
+        assert!((longest_run(&epsilon) - 0.180609).abs() < 1e-4);
+    }
+}
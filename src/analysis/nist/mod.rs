@@ -0,0 +1,204 @@
+//! Statistical tests of NIST SP 800-22 rev. 1a, computing the same p-values as
+//! the `assess` tool of the reference implementation (sts-2.1.2).
+//! Sequences are slices of bits, one bit (0 or 1) per byte.
+
+pub mod complexity;
+pub mod dft;
+pub mod excursions;
+pub mod final_report;
+pub mod frequency;
+pub mod rank;
+pub mod serial;
+pub mod summary;
+pub mod template;
+pub mod universal;
+
+pub use final_report::FinalReport;
+pub use summary::{ParsedReport, Summary};
+
+// one block of the overlapping template test, enough for a 32x32 matrix of the rank test
+// and for blocks of the longest run test as well
+const MIN_LENGTH: usize = 1032;
+// lengths of the templates shipped with the reference implementation
+const TEMPLATE_LENGTHS: std::ops::RangeInclusive<usize> = 2..=21;
+
+/// Parameters of the tests, defaults are the defaults of `assess`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parameters {
+    pub block_frequency: usize,
+    pub non_overlapping_template: usize,
+    pub overlapping_template: usize,
+    pub approximate_entropy: usize,
+    pub serial: usize,
+    pub linear_complexity: usize,
+}
+
+impl Parameters {
+    /// Checks that every test is defined for these parameters and sequences of `n` bits.
+    pub fn check(&self, n: usize) -> Result<(), String> {
+        if n < MIN_LENGTH {
+            return Err(format!("sequences must have at least {} bits", MIN_LENGTH));
+        }
+        for (name, block) in [
+            ("block frequency", self.block_frequency),
+            ("linear complexity", self.linear_complexity),
+        ] {
+            if block == 0 || block > n {
+                return Err(format!("{} block length must be between 1 and {}", name, n));
+            }
+        }
+        for (name, m) in [
+            ("non-overlapping", self.non_overlapping_template),
+            ("overlapping", self.overlapping_template),
+        ] {
+            if !TEMPLATE_LENGTHS.contains(&m) {
+                return Err(format!(
+                    "{} template length must be between {} and {}",
+                    name,
+                    TEMPLATE_LENGTHS.start(),
+                    TEMPLATE_LENGTHS.end()
+                ));
+            }
+        }
+        if self.approximate_entropy == 0 {
+            return Err("approximate entropy block length must be positive".to_string());
+        }
+        if self.serial < 2 {
+            return Err("serial block length must be at least 2".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Parameters {
+            block_frequency: 128,
+            non_overlapping_template: 9,
+            overlapping_template: 9,
+            approximate_entropy: 10,
+            serial: 16,
+            linear_complexity: 500,
+        }
+    }
+}
+
+/// P-values of a single test for one sequence, `None` if the test is not applicable
+/// to the sequence (random excursions with too few cycles, universal for too short sequences).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestOutcome {
+    pub name: &'static str,
+    pub p_values: Option<Vec<f64>>,
+}
+
+impl TestOutcome {
+    fn new(name: &'static str, p_values: Vec<f64>) -> Self {
+        TestOutcome {
+            name,
+            p_values: Some(p_values),
+        }
+    }
+}
+
+pub fn bits_from_bytes(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1))
+        .collect()
+}
+
+/// Runs all tests on a single sequence, in the order of the final analysis report,
+/// returning an error if [`Parameters::check`] rejects the parameters or the sequence length.
+pub fn run_tests(bits: &[u8], parameters: &Parameters) -> Result<Vec<TestOutcome>, String> {
+    parameters.check(bits.len())?;
+    Ok(vec![
+        TestOutcome::new("Frequency", vec![frequency::frequency(bits)]),
+        TestOutcome::new(
+            "BlockFrequency",
+            vec![frequency::block_frequency(bits, parameters.block_frequency)],
+        ),
+        TestOutcome::new("CumulativeSums", frequency::cumulative_sums(bits).to_vec()),
+        TestOutcome::new("Runs", vec![frequency::runs(bits)]),
+        TestOutcome::new("LongestRun", vec![frequency::longest_run(bits)]),
+        TestOutcome::new("Rank", vec![rank::rank(bits)]),
+        TestOutcome::new("FFT", vec![dft::dft(bits)]),
+        TestOutcome::new(
+            "NonOverlappingTemplate",
+            template::non_overlapping_template(bits, parameters.non_overlapping_template),
+        ),
+        TestOutcome::new(
+            "OverlappingTemplate",
+            vec![template::overlapping_template(
+                bits,
+                parameters.overlapping_template,
+            )],
+        ),
+        TestOutcome {
+            name: "Universal",
+            p_values: universal::universal(bits).map(|p| vec![p]),
+        },
+        TestOutcome::new(
+            "ApproximateEntropy",
+            vec![serial::approximate_entropy(
+                bits,
+                parameters.approximate_entropy,
+            )],
+        ),
+        TestOutcome {
+            name: "RandomExcursions",
+            p_values: excursions::random_excursions(bits),
+        },
+        TestOutcome {
+            name: "RandomExcursionsVariant",
+            p_values: excursions::random_excursions_variant(bits),
+        },
+        TestOutcome::new("Serial", serial::serial(bits, parameters.serial).to_vec()),
+        TestOutcome::new(
+            "LinearComplexity",
+            vec![complexity::linear_complexity(
+                bits,
+                parameters.linear_complexity,
+            )],
+        ),
+    ])
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    // example sequence used throughout SP 800-22 (first 100 bits of the expansion of pi)
+    pub const EPSILON_100: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+
+    pub fn bits(s: &str) -> Vec<u8> {
+        s.bytes().map(|b| b - b'0').collect()
+    }
+
+    #[test]
+    fn test_check_parameters() {
+        let parameters = Parameters::default();
+        assert!(run_tests(&vec![0; MIN_LENGTH], &parameters).is_ok());
+        assert!(run_tests(&bits(EPSILON_100), &parameters).is_err());
+        for parameters in [
+            Parameters {
+                serial: 1,
+                ..parameters
+            },
+            Parameters {
+                block_frequency: 0,
+                ..parameters
+            },
+            Parameters {
+                overlapping_template: 1100,
+                ..parameters
+            },
+        ] {
+            assert!(parameters.check(MIN_LENGTH).is_err());
+        }
+    }
+
+    #[test]
+    fn test_bits_from_bytes() {
+        assert_eq!(bits_from_bytes(&[0b1010_0001]), bits("10100001"));
+    }
+}
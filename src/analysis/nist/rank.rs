@@ -0,0 +1,51 @@
+use crate::analysis::linspan::compute_rank;
+
+const SIZE: usize = 32;
+
+// probability that a random SIZE x SIZE binary matrix has given rank
+fn rank_probability(rank: usize) -> f64 {
+    let product = (0..rank)
+        .map(|i| {
+            let a = 1.0 - 2f64.powi(i as i32 - SIZE as i32);
+            a * a / (1.0 - 2f64.powi(i as i32 - rank as i32))
+        })
+        .product::<f64>();
+    2f64.powi((rank * (2 * SIZE - rank)) as i32 - (SIZE * SIZE) as i32) * product
+}
+
+/// Binary matrix rank test on disjoint 32x32 matrices.
+pub fn rank(bits: &[u8]) -> f64 {
+    let mut counts = [0u64; 3];
+    for matrix in bits.chunks_exact(SIZE * SIZE) {
+        let rows = matrix
+            .chunks_exact(SIZE)
+            .map(|row| row.iter().fold(0u128, |acc, &b| acc << 1 | b as u128))
+            .collect();
+        match compute_rank(rows) {
+            SIZE => counts[0] += 1,
+            r if r == SIZE - 1 => counts[1] += 1,
+            _ => counts[2] += 1,
+        }
+    }
+    let full = rank_probability(SIZE);
+    let full_minus_one = rank_probability(SIZE - 1);
+    let probabilities = [full, full_minus_one, 1.0 - full - full_minus_one];
+    let matrices = (bits.len() / (SIZE * SIZE)) as f64;
+    let chi_squared = counts
+        .iter()
+        .zip(probabilities)
+        .map(|(&f, p)| (f as f64 - matrices * p).powi(2) / (matrices * p))
+        .sum::<f64>();
+    (-chi_squared / 2.0).exp()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rank_probabilities() {
+        assert!((rank_probability(32) - 0.2888).abs() < 1e-4);
+        assert!((rank_probability(31) - 0.5776).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::analysis::stats::igamc;
+
+// numbers of occurrences of all overlapping `m` bit patterns, with the sequence wrapped around
+fn pattern_counts(bits: &[u8], m: usize) -> Vec<u64> {
+    let mut counts = vec![0; 1 << m];
+    if m == 0 {
+        return counts;
+    }
+    let mask = (1 << m) - 1;
+    let mut value = bits[..m - 1]
+        .iter()
+        .fold(0usize, |acc, &b| acc << 1 | b as usize);
+    for &b in bits[m - 1..].iter().chain(&bits[..m - 1]) {
+        value = (value << 1 | b as usize) & mask;
+        counts[value] += 1;
+    }
+    counts
+}
+
+fn psi_squared(bits: &[u8], m: usize) -> f64 {
+    if m == 0 {
+        return 0.0;
+    }
+    let n = bits.len() as f64;
+    let sum = pattern_counts(bits, m)
+        .iter()
+        .map(|&c| (c * c) as f64)
+        .sum::<f64>();
+    sum * 2f64.powi(m as i32) / n - n
+}
+
+/// Serial test with patterns of `m` bits, both p-values; `m` must be at least 2.
+pub fn serial(bits: &[u8], m: usize) -> [f64; 2] {
+    let psi_m = psi_squared(bits, m);
+    let psi_m1 = psi_squared(bits, m - 1);
+    let psi_m2 = psi_squared(bits, m.saturating_sub(2));
+    let del1 = psi_m - psi_m1;
+    let del2 = psi_m - 2.0 * psi_m1 + psi_m2;
+    [
+        igamc(2f64.powi(m as i32 - 2), del1 / 2.0),
+        igamc(2f64.powi(m as i32 - 3), del2 / 2.0),
+    ]
+}
+
+fn phi(bits: &[u8], m: usize) -> f64 {
+    if m == 0 {
+        return 0.0;
+    }
+    let n = bits.len() as f64;
+    pattern_counts(bits, m)
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| c as f64 * (c as f64 / n).ln())
+        .sum::<f64>()
+        / n
+}
+
+/// Approximate entropy test with patterns of `m` and `m + 1` bits.
+pub fn approximate_entropy(bits: &[u8], m: usize) -> f64 {
+    let n = bits.len() as f64;
+    let apen = phi(bits, m) - phi(bits, m + 1);
+    let chi_squared = 2.0 * n * (2f64.ln() - apen);
+    igamc(2f64.powi(m as i32 - 1), chi_squared / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::nist::test::{bits, EPSILON_100};
+
+    #[test]
+    fn test_sp800_22_examples() {
+        let [p1, p2] = serial(&bits("0011011101"), 3);
+        assert!((p1 - 0.808792).abs() < 1e-6, "{}", p1);
+        assert!((p2 - 0.670320).abs() < 1e-6, "{}", p2);
+        let p = approximate_entropy(&bits(EPSILON_100), 2);
+        assert!((p - 0.235301).abs() < 1e-6, "{}", p);
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use super::final_report::{min_pass_count, UNIFORMITY_ALPHA};
+
+/// Line of a final analysis report read back from text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedRow {
+    pub name: String,
+    pub histogram: [u64; 10],
+    /// P-value of the uniformity test, `None` if the test was not applicable.
+    pub uniformity: Option<f64>,
+    pub passed: usize,
+    pub samples: usize,
+}
+
+impl ParsedRow {
+    fn parse(line: &str) -> Option<ParsedRow> {
+        let tokens = line
+            .split_whitespace()
+            .filter(|&t| t != "*")
+            .collect::<Vec<&str>>();
+        if tokens.len() != 13 {
+            return None;
+        }
+        let mut histogram = [0; 10];
+        for (count, token) in histogram.iter_mut().zip(&tokens) {
+            *count = token.parse().ok()?;
+        }
+        let uniformity = match tokens[10] {
+            "----" => None,
+            p => Some(p.parse().ok()?),
+        };
+        let (passed, samples) = match tokens[11].split_once('/') {
+            Some((passed, samples)) => (passed.parse().ok()?, samples.parse().ok()?),
+            None if tokens[11] == "------" => (0, 0),
+            None => return None,
+        };
+        Some(ParsedRow {
+            name: tokens[12].to_string(),
+            histogram,
+            uniformity,
+            passed,
+            samples,
+        })
+    }
+
+    pub fn proportion(&self) -> f64 {
+        self.passed as f64 / self.samples as f64
+    }
+
+    /// True if the proportion of passing sequences or uniformity of p-values is below
+    /// the acceptance threshold of SP 800-22.
+    pub fn failed(&self) -> bool {
+        self.samples > 0
+            && (self.passed < min_pass_count(self.samples)
+                || self.uniformity.is_some_and(|p| p < UNIFORMITY_ALPHA))
+    }
+}
+
+/// Contents of a `finalAnalysisReport.txt` file, written either by `assess`
+/// or by [`FinalReport`](super::FinalReport).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedReport {
+    pub generator: String,
+    pub rows: Vec<ParsedRow>,
+}
+
+impl ParsedReport {
+    pub fn parse(text: &str) -> Result<ParsedReport, String> {
+        let generator = text
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("generator is <"))
+            .and_then(|rest| rest.strip_suffix('>'))
+            .ok_or("missing generator line")?
+            .to_string();
+        let rows = text
+            .lines()
+            .filter_map(ParsedRow::parse)
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            return Err("no test results".to_string());
+        }
+        Ok(ParsedReport { generator, rows })
+    }
+}
+
+/// Results of all lines of one test (e.g. all templates) in one report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestSummary {
+    pub dataset: String,
+    pub test: String,
+    pub lines: usize,
+    pub failed_lines: usize,
+    /// Minimal uniformity p-value and proportion of passing sequences over the lines,
+    /// `None` if the test was not applicable to any sequence.
+    pub min_uniformity: Option<f64>,
+    pub min_proportion: Option<f64>,
+}
+
+impl TestSummary {
+    pub fn passed(&self) -> bool {
+        self.failed_lines == 0
+    }
+}
+
+/// Table of results of every test for every dataset.
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    pub tests: Vec<TestSummary>,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Summary::default()
+    }
+
+    /// Adds all tests of the report, named by the generator of the report.
+    pub fn add(&mut self, report: &ParsedReport) {
+        let start = self.tests.len();
+        for row in &report.rows {
+            let test = match self.tests[start..].iter_mut().find(|t| t.test == row.name) {
+                Some(test) => test,
+                None => {
+                    self.tests.push(TestSummary {
+                        dataset: report.generator.clone(),
+                        test: row.name.clone(),
+                        lines: 0,
+                        failed_lines: 0,
+                        min_uniformity: None,
+                        min_proportion: None,
+                    });
+                    self.tests.last_mut().unwrap()
+                }
+            };
+            test.lines += 1;
+            test.failed_lines += row.failed() as usize;
+            if row.samples > 0 {
+                test.min_proportion = Some(
+                    test.min_proportion
+                        .map_or(row.proportion(), |p| p.min(row.proportion())),
+                );
+            }
+            if let Some(uniformity) = row.uniformity {
+                test.min_uniformity = Some(
+                    test.min_uniformity
+                        .map_or(uniformity, |p| p.min(uniformity)),
+                );
+            }
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.tests.iter().all(|t| t.passed())
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .tests
+            .iter()
+            .map(|t| t.dataset.len())
+            .max()
+            .unwrap_or(0)
+            .max("DATASET".len());
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{:width$}  {:23}  LINES  FAILED  MIN P-VALUE  MIN PROPORTION  RESULT",
+            "DATASET", "STATISTICAL TEST"
+        )?;
+        for test in &self.tests {
+            let format =
+                |value: Option<f64>| value.map_or("----".to_string(), |v| format!("{:.6}", v));
+            let result = match (test.min_proportion, test.passed()) {
+                (None, _) => "n/a",
+                (_, true) => "PASS",
+                (_, false) => "FAIL",
+            };
+            writeln!(
+                out,
+                "{:width$}  {:23}  {:5}  {:6}  {:>11}  {:>14}  {}",
+                test.dataset,
+                test.test,
+                test.lines,
+                test.failed_lines,
+                format(test.min_uniformity),
+                format(test.min_proportion),
+                result
+            )?;
+        }
+        write!(f, "{}", out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::nist::{FinalReport, TestOutcome};
+
+    #[test]
+    fn test_parse_reports() {
+        let row = ParsedRow::parse(
+            " 12  10   8  11   9  10  10  10  10  10  0.000001 *  95/100  *  Frequency",
+        )
+        .unwrap();
+        assert_eq!(row.histogram[0], 12);
+        assert_eq!((row.passed, row.samples), (95, 100));
+        assert!(row.failed());
+        assert_eq!(ParsedRow::parse("   generator is <data>"), None);
+
+        let mut report = FinalReport::new("data.blb");
+        for p in [0.05, 0.15, 0.25, 0.35, 0.45, 0.55, 0.65, 0.75, 0.85, 0.95] {
+            report.add(&[
+                TestOutcome::new("Frequency", vec![p]),
+                TestOutcome::new("Serial", vec![p, 0.001]),
+                TestOutcome {
+                    name: "Universal",
+                    p_values: None,
+                },
+            ]);
+        }
+        let parsed = ParsedReport::parse(&report.to_string()).unwrap();
+        assert_eq!(parsed.generator, "data.blb");
+        assert_eq!(parsed.rows.len(), 4);
+        assert_eq!(parsed.rows[0].histogram, [1; 10]);
+
+        let mut summary = Summary::new();
+        summary.add(&parsed);
+        let tests = &summary.tests;
+        assert_eq!(tests.len(), 3);
+        assert!(tests[0].passed());
+        assert_eq!((tests[1].lines, tests[1].failed_lines), (2, 1));
+        assert_eq!(tests[1].min_proportion, Some(0.0));
+        assert_eq!(tests[2].min_proportion, None);
+        assert!(!summary.passed());
+    }
+}
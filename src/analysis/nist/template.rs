@@ -0,0 +1,131 @@
+use crate::analysis::stats::{igamc, ln_gamma};
+
+const NON_OVERLAPPING_BLOCKS: usize = 8;
+const OVERLAPPING_BLOCK: usize = 1032;
+const OVERLAPPING_CLASSES: usize = 5;
+
+/// All aperiodic templates of `m` bits (which cannot overlap with themselves)
+/// in increasing order, as in the template files of the reference implementation.
+pub fn aperiodic_templates(m: usize) -> Vec<Vec<u8>> {
+    (0..1usize << m)
+        .map(|x| {
+            (0..m)
+                .rev()
+                .map(|i| ((x >> i) & 1) as u8)
+                .collect::<Vec<u8>>()
+        })
+        .filter(|t| (1..m).all(|shift| t[shift..] != t[..m - shift]))
+        .collect()
+}
+
+// values of all windows of `m` bits, windows[i] is bits[i..i + m] read as a binary number
+fn windows(bits: &[u8], m: usize) -> Vec<usize> {
+    bits.windows(m)
+        .map(|w| w.iter().fold(0, |acc, &b| acc << 1 | b as usize))
+        .collect()
+}
+
+fn value(template: &[u8]) -> usize {
+    template.iter().fold(0, |acc, &b| acc << 1 | b as usize)
+}
+
+/// Non-overlapping template matching test, one p-value for each aperiodic template of `m` bits.
+pub fn non_overlapping_template(bits: &[u8], m: usize) -> Vec<f64> {
+    let windows = windows(bits, m);
+    aperiodic_templates(m)
+        .iter()
+        .map(|template| {
+            non_overlapping(
+                &windows,
+                bits.len(),
+                value(template),
+                m,
+                NON_OVERLAPPING_BLOCKS,
+            )
+        })
+        .collect()
+}
+
+pub fn non_overlapping_p_value(bits: &[u8], template: &[u8], blocks: usize) -> f64 {
+    let m = template.len();
+    non_overlapping(&windows(bits, m), bits.len(), value(template), m, blocks)
+}
+
+fn non_overlapping(windows: &[usize], n: usize, template: usize, m: usize, blocks: usize) -> f64 {
+    let block = n / blocks;
+    let mean = (block - m + 1) as f64 / 2f64.powi(m as i32);
+    let variance =
+        block as f64 * (1.0 / 2f64.powi(m as i32) - (2 * m - 1) as f64 / 2f64.powi(2 * m as i32));
+    let chi_squared = (0..blocks)
+        .map(|i| {
+            let block_windows = &windows[i * block..i * block + block - m + 1];
+            let mut matches = 0;
+            let mut j = 0;
+            while j < block_windows.len() {
+                if block_windows[j] == template {
+                    matches += 1;
+                    j += m;
+                } else {
+                    j += 1;
+                }
+            }
+            (matches as f64 - mean).powi(2) / variance
+        })
+        .sum::<f64>();
+    igamc(blocks as f64 / 2.0, chi_squared / 2.0)
+}
+
+// probability of `u` overlapping matches in a block, as in the reference implementation
+fn matches_probability(u: usize, eta: f64) -> f64 {
+    if u == 0 {
+        return (-eta).exp();
+    }
+    (1..=u)
+        .map(|l| {
+            (-eta - u as f64 * 2f64.ln() + l as f64 * eta.ln() - ln_gamma(l as f64 + 1.0)
+                + ln_gamma(u as f64)
+                - ln_gamma(l as f64)
+                - ln_gamma((u - l) as f64 + 1.0))
+            .exp()
+        })
+        .sum()
+}
+
+/// Overlapping template matching test with the template of `m` ones.
+pub fn overlapping_template(bits: &[u8], m: usize) -> f64 {
+    let blocks = bits.len() / OVERLAPPING_BLOCK;
+    let lambda = (OVERLAPPING_BLOCK - m + 1) as f64 / 2f64.powi(m as i32);
+    let eta = lambda / 2.0;
+    let mut probabilities = (0..OVERLAPPING_CLASSES)
+        .map(|u| matches_probability(u, eta))
+        .collect::<Vec<f64>>();
+    probabilities.push(1.0 - probabilities.iter().sum::<f64>());
+
+    let mut counts = [0u64; OVERLAPPING_CLASSES + 1];
+    for chunk in bits.chunks_exact(OVERLAPPING_BLOCK) {
+        let matches = chunk
+            .windows(m)
+            .filter(|w| w.iter().all(|&b| b == 1))
+            .count();
+        counts[matches.min(OVERLAPPING_CLASSES)] += 1;
+    }
+    let chi_squared = counts
+        .iter()
+        .zip(&probabilities)
+        .map(|(&v, p)| (v as f64 - blocks as f64 * p).powi(2) / (blocks as f64 * p))
+        .sum::<f64>();
+    igamc(OVERLAPPING_CLASSES as f64 / 2.0, chi_squared / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::nist::test::bits;
+
+    #[test]
+    fn test_sp800_22_example() {
+        assert_eq!(aperiodic_templates(9).len(), 148);
+        let p = non_overlapping_p_value(&bits("10100100101110010110"), &bits("001"), 2);
+        assert!((p - 0.344154).abs() < 1e-6, "{}", p);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::analysis::stats::erfc;
+
+// minimal sequence lengths for block lengths from 6 to 16
+const MIN_LENGTHS: [usize; 11] = [
+    387_840,
+    904_960,
+    2_068_480,
+    4_654_080,
+    10_342_400,
+    22_753_280,
+    49_643_520,
+    107_560_960,
+    231_669_760,
+    496_435_200,
+    1_059_061_760,
+];
+const EXPECTED: [f64; 11] = [
+    5.2177052, 6.1962507, 7.1836656, 8.1764248, 9.1723243, 10.170032, 11.168765, 12.168070,
+    13.167693, 14.167488, 15.167379,
+];
+const VARIANCE: [f64; 11] = [
+    2.954, 3.125, 3.238, 3.311, 3.356, 3.384, 3.401, 3.410, 3.416, 3.419, 3.421,
+];
+
+/// Maurer's universal statistical test, `None` for sequences shorter than 387840 bits.
+pub fn universal(bits: &[u8]) -> Option<f64> {
+    let n = bits.len();
+    let index = MIN_LENGTHS.iter().rposition(|&min| n >= min)?;
+    let l = index + 6;
+    let q = 10 * (1 << l);
+    let k = n / l - q;
+
+    let value = |i: usize| {
+        bits[i * l..(i + 1) * l]
+            .iter()
+            .fold(0usize, |acc, &b| acc << 1 | b as usize)
+    };
+    let mut last = vec![0usize; 1 << l];
+    for i in 0..q {
+        last[value(i)] = i + 1;
+    }
+    let mut sum = 0.0;
+    for i in q..q + k {
+        let v = value(i);
+        sum += ((i + 1 - last[v]) as f64).log2();
+        last[v] = i + 1;
+    }
+    let phi = sum / k as f64;
+
+    let c =
+        0.7 - 0.8 / l as f64 + (4.0 + 32.0 / l as f64) * (k as f64).powf(-3.0 / l as f64) / 15.0;
+    let sigma = c * (VARIANCE[index] / k as f64).sqrt();
+    Some(erfc((phi - EXPECTED[index]).abs() / (2f64.sqrt() * sigma)))
+}
@@ -0,0 +1,116 @@
+use rand::Rng;
+
+use super::{
+    collision::collision_test,
+    coverage::coverage_test,
+    linspan::linear_span_test,
+    random_blocks,
+    sac::{base_sac_test, sac_test},
+    stats::TestResult,
+    BlockCipherUnderTest,
+};
+
+/// Sample sizes for each test of the suite.
+#[derive(Clone, Copy, Debug)]
+pub struct Samples {
+    pub sac: usize,
+    pub base_sac: usize,
+    pub collision: usize,
+    pub coverage: usize,
+    pub linspan: usize,
+}
+
+impl Default for Samples {
+    fn default() -> Self {
+        Samples {
+            sac: 4096,
+            base_sac: 4096,
+            collision: 256,
+            coverage: 256,
+            linspan: 1024,
+        }
+    }
+}
+
+/// Runs SAC, base SAC, collision, coverage and linear span tests, in that order.
+pub fn run_suite<C, R>(cipher: &C, rng: &mut R, samples: &Samples) -> Vec<TestResult>
+where
+    C: BlockCipherUnderTest + ?Sized,
+    R: Rng,
+{
+    vec![
+        sac_test(cipher, &random_blocks(rng, samples.sac)),
+        base_sac_test(cipher, &random_blocks(rng, samples.base_sac)),
+        collision_test(cipher, &random_blocks(rng, samples.collision)),
+        coverage_test(cipher, &random_blocks(rng, samples.coverage)),
+        linear_span_test(cipher, rng, samples.linspan),
+    ]
+}
+
+/// Table with one row of test results per label (e.g. number of rounds).
+pub struct Report {
+    pub label: String,
+    pub rows: Vec<(String, Vec<TestResult>)>,
+}
+
+impl Report {
+    pub fn new(label: &str) -> Self {
+        Report {
+            label: label.to_string(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, label: String, results: Vec<TestResult>) {
+        self.rows.push((label, results))
+    }
+
+    fn header(&self) -> Vec<String> {
+        let mut header = vec![self.label.clone()];
+        if let Some((_, results)) = self.rows.first() {
+            for result in results {
+                header.push(format!("{} statistic", result.name));
+                header.push(format!("{} p-value", result.name));
+                header.push(format!("{} passed", result.name));
+            }
+        }
+        header.push("all passed".to_string());
+        header
+    }
+
+    fn cells(&self) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|(label, results)| {
+                let mut row = vec![label.clone()];
+                for result in results {
+                    row.push(format!("{:.4}", result.statistic));
+                    row.push(format!("{:.4}", result.p_value));
+                    row.push(result.passed.to_string());
+                }
+                row.push(results.iter().all(|r| r.passed).to_string());
+                row
+            })
+            .collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![self.header().join(",")];
+        lines.extend(self.cells().iter().map(|row| row.join(",")));
+        lines.join("\n") + "\n"
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let header = self.header();
+        let mut lines = vec![
+            format!("| {} |", header.join(" | ")),
+            format!("|{}", "---|".repeat(header.len())),
+        ];
+        lines.extend(
+            self.cells()
+                .iter()
+                .map(|row| format!("| {} |", row.join(" | "))),
+        );
+        lines.join("\n") + "\n"
+    }
+}
@@ -0,0 +1,174 @@
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{
+    check_bases, check_ones,
+    stats::{binomial_cdf, TestResult},
+    xor_array, Block, BlockCipherUnderTest,
+};
+use crate::bits::{BasesOne, BitsOne, INPUT_SIZE_BYTES};
+
+const BLOCK_BITS: usize = INPUT_SIZE_BYTES * 8;
+const BLOCK_BASES: usize = INPUT_SIZE_BYTES * 4;
+// probability that a base of a random permutation output changes
+const BASE_CHANGE: f64 = 0.75;
+// standard normal quantiles splitting the distribution into 5 equally probable bins
+const QUANTILES: [f64; 4] = [-0.8416, -0.2533, 0.2533, 0.8416];
+
+/// Exclusive bin upper bounds and their probabilities for counts of changed outputs
+/// after `samples` single input changes, each count ~ Binomial(samples, p).
+fn bins(samples: usize, p: f64) -> ([u64; 4], [f64; 5]) {
+    let n = samples as f64;
+    let bounds = QUANTILES.map(|z| (n * p + z * (n * p * (1.0 - p)).sqrt()).floor() as u64);
+    let mut probabilities = [0.0; 5];
+    let mut previous = 0.0;
+    for (i, &bound) in bounds.iter().enumerate() {
+        let cdf = binomial_cdf(bound, samples as u64, p);
+        probabilities[i] = cdf - previous;
+        previous = cdf;
+    }
+    probabilities[4] = 1.0 - previous;
+    (bounds, probabilities)
+}
+
+/// Counts for every pair of input and output bits how many times the output bit
+/// changed after flipping the input bit.
+pub fn sac_matrix<C>(cipher: &C, texts: &[Block]) -> Vec<[u32; BLOCK_BITS]>
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    texts
+        .par_iter()
+        .fold(
+            || vec![[0; BLOCK_BITS]; BLOCK_BITS],
+            |mut matrix, &input| {
+                let fst_output = cipher.encrypt_block(input);
+                for (bits, results) in BitsOne::new(input).zip(&mut matrix) {
+                    let xored = xor_array(fst_output, cipher.encrypt_block(bits));
+                    for (i, v) in xored.iter().flat_map(check_ones).enumerate() {
+                        results[i] += v;
+                    }
+                }
+                matrix
+            },
+        )
+        .reduce(
+            || vec![[0; BLOCK_BITS]; BLOCK_BITS],
+            |mut acc, x| {
+                for (acc, x) in acc.iter_mut().zip(x) {
+                    for (a, b) in acc.iter_mut().zip(x) {
+                        *a += b;
+                    }
+                }
+                acc
+            },
+        )
+}
+
+/// Strict avalanche criterion test: every output bit should change with probability 1/2
+/// after flipping any single input bit.
+pub fn sac_test<C>(cipher: &C, texts: &[Block]) -> TestResult
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    let matrix = sac_matrix(cipher, texts);
+    chi_square("sac", matrix.iter().flatten(), texts.len(), 0.5)
+}
+
+fn chi_square<'a>(
+    name: &'static str,
+    counts: impl Iterator<Item = &'a u32>,
+    samples: usize,
+    p: f64,
+) -> TestResult {
+    let (bounds, probabilities) = bins(samples, p);
+    let mut bins = vec![0; 5];
+    for &x in counts {
+        let bin = bounds.iter().take_while(|&&b| x as u64 >= b).count();
+        bins[bin] += 1;
+    }
+    TestResult::chi_square(name, bins, &probabilities)
+}
+
+/// Counts for every pair of input and output bases how many times the output base
+/// changed after replacing the input base with each of its three alternatives.
+pub fn base_sac_matrix<C>(cipher: &C, texts: &[Block]) -> Vec<[u32; BLOCK_BASES]>
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    texts
+        .par_iter()
+        .fold(
+            || vec![[0; BLOCK_BASES]; BLOCK_BASES],
+            |mut matrix, &input| {
+                let fst_output = cipher.encrypt_block(input);
+                for (i, bases) in BasesOne::new(input).enumerate() {
+                    let xored = xor_array(fst_output, cipher.encrypt_block(bases));
+                    for (j, v) in xored.iter().flat_map(check_bases).enumerate() {
+                        matrix[i / 3][j] += v;
+                    }
+                }
+                matrix
+            },
+        )
+        .reduce(
+            || vec![[0; BLOCK_BASES]; BLOCK_BASES],
+            |mut acc, x| {
+                for (acc, x) in acc.iter_mut().zip(x) {
+                    for (a, b) in acc.iter_mut().zip(x) {
+                        *a += b;
+                    }
+                }
+                acc
+            },
+        )
+}
+
+/// Probability of change of every output base after a single base change of the input,
+/// 3/4 is expected for every base.
+pub fn base_change_probabilities(matrix: &[[u32; BLOCK_BASES]], texts: usize) -> Vec<f64> {
+    let changes = (matrix.len() * 3 * texts) as f64;
+    (0..BLOCK_BASES)
+        .map(|j| matrix.iter().map(|row| row[j] as f64).sum::<f64>() / changes)
+        .collect()
+}
+
+/// Quaternary strict avalanche criterion test: every output base should change
+/// with probability 3/4 after replacing any single input base.
+pub fn base_sac_test<C>(cipher: &C, texts: &[Block]) -> TestResult
+where
+    C: BlockCipherUnderTest + ?Sized,
+{
+    base_sac_result(&base_sac_matrix(cipher, texts), texts.len())
+}
+
+/// Result of [`base_sac_test`] for a matrix computed by [`base_sac_matrix`] from `texts` inputs.
+pub fn base_sac_result(matrix: &[[u32; BLOCK_BASES]], texts: usize) -> TestResult {
+    chi_square("base sac", matrix.iter().flatten(), texts * 3, BASE_CHANGE)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::{random_blocks, BlockDNAC};
+
+    #[test]
+    fn test_bins() {
+        // bounds hard-coded in the original test for 2^20 samples
+        let (bounds, probabilities) = bins(1 << 20, 0.5);
+        assert_eq!(bounds, [523857, 524158, 524417, 524718]);
+        assert!((probabilities[0] - 0.200224).abs() < 1e-3);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        let (_, probabilities) = bins(3 << 12, BASE_CHANGE);
+        assert!(probabilities.iter().all(|p| (p - 0.2).abs() < 0.02));
+    }
+
+    #[test]
+    fn test_base_sac() {
+        let cipher = BlockDNAC::new(&[0; 16], 22).unwrap();
+        let texts = random_blocks(&mut rand::thread_rng(), 64);
+        let matrix = base_sac_matrix(&cipher, &texts);
+        assert!(matrix.iter().flatten().all(|&c| c <= 3 * 64));
+        let probabilities = base_change_probabilities(&matrix, texts.len());
+        assert!(probabilities.iter().all(|p| (p - BASE_CHANGE).abs() < 0.1));
+    }
+}
@@ -0,0 +1,191 @@
+use std::f64::consts::PI;
+
+// significance level used by all tests, chi-square thresholds in the original tests
+// (13.277 for 4 and 9.210 for 2 degrees of freedom) correspond to it
+pub const ALPHA: f64 = 0.01;
+
+const EPSILON: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 10_000;
+
+/// Outcome of a single statistical test.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub p_value: f64,
+    pub passed: bool,
+    // observed counts in each bin
+    pub bins: Vec<u64>,
+}
+
+impl TestResult {
+    pub fn new(name: &'static str, statistic: f64, p_value: f64, bins: Vec<u64>) -> Self {
+        TestResult {
+            name,
+            statistic,
+            p_value,
+            passed: p_value >= ALPHA,
+            bins,
+        }
+    }
+
+    /// Chi-square goodness of fit test of `bins` against expected `probabilities`.
+    pub fn chi_square(name: &'static str, bins: Vec<u64>, probabilities: &[f64]) -> Self {
+        let statistic = chi_square(&bins, probabilities);
+        let p_value = chi_square_p_value(statistic, bins.len() - 1);
+        TestResult::new(name, statistic, p_value, bins)
+    }
+}
+
+pub fn chi_square(bins: &[u64], probabilities: &[f64]) -> f64 {
+    let total = bins.iter().sum::<u64>() as f64;
+    bins.iter()
+        .zip(probabilities)
+        .map(|(&o, p)| {
+            let e = p * total;
+            (o as f64 - e).powi(2) / e
+        })
+        .sum()
+}
+
+pub fn chi_square_p_value(statistic: f64, degrees_of_freedom: usize) -> f64 {
+    igamc(degrees_of_freedom as f64 / 2.0, statistic / 2.0)
+}
+
+// Lanczos approximation
+pub fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection formula
+        (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x)
+    } else {
+        let x = x - 1.0;
+        let t = x + G + 0.5;
+        let sum = COEFFICIENTS[1..]
+            .iter()
+            .enumerate()
+            .fold(COEFFICIENTS[0], |acc, (i, c)| {
+                acc + c / (x + i as f64 + 1.0)
+            });
+        0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+    }
+}
+
+/// Regularized lower incomplete gamma function P(a, x).
+pub fn igam(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= a + 1.0 {
+        return 1.0 - igamc(a, x);
+    }
+    // series expansion
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut n = a;
+    for _ in 0..MAX_ITERATIONS {
+        n += 1.0;
+        term *= x / n;
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+/// Regularized upper incomplete gamma function Q(a, x) = 1 - P(a, x).
+pub fn igamc(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    if x < a + 1.0 {
+        return 1.0 - igam(a, x);
+    }
+    // continued fraction (modified Lentz's method)
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+/// Complementary error function.
+pub fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        2.0 - erfc(-x)
+    } else {
+        igamc(0.5, x * x)
+    }
+}
+
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / 2f64.sqrt())
+}
+
+/// P(X < k) for X ~ Binomial(n, p).
+pub fn binomial_cdf(k: u64, n: u64, p: f64) -> f64 {
+    if k == 0 {
+        return 0.0;
+    }
+    if k > n {
+        return 1.0;
+    }
+    let ln_n = ln_gamma(n as f64 + 1.0);
+    // terms further than 40 standard deviations from the mean are negligible
+    let sigma = (n as f64 * p * (1.0 - p)).sqrt();
+    let start = (n as f64 * p - 40.0 * sigma).max(0.0) as u64;
+    (start..k)
+        .map(|i| {
+            (ln_n - ln_gamma(i as f64 + 1.0) - ln_gamma((n - i) as f64 + 1.0)
+                + i as f64 * p.ln()
+                + (n - i) as f64 * (1.0 - p).ln())
+            .exp()
+        })
+        .sum::<f64>()
+        .min(1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chi_square_p_value() {
+        // lookup table values used by the original tests
+        assert!((chi_square_p_value(13.277, 4) - 0.01).abs() < 1e-4);
+        assert!((chi_square_p_value(9.210, 2) - 0.01).abs() < 1e-4);
+        assert!((erfc(1.0) - 0.157_299_207_050_285_1).abs() < 1e-12);
+        assert!((binomial_cdf(3, 4, 0.5) - 11.0 / 16.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,162 @@
+//! Timing leakage test in the style of dudect: encryption times of a fixed plaintext
+//! and of random plaintexts, measured in random order, are compared with Welch's
+//! t-test, also after cropping the slowest measurements.
+
+use std::{hint::black_box, time::Instant};
+
+use rand::Rng;
+
+use crate::{analysis::random_dna, dna::DNA, DNAC, INPUT_SIZE};
+
+/// |t| above which dudect reports a definite leak.
+pub const T_THRESHOLD: f64 = 4.5;
+
+/// Fractions of the fastest measurements kept by each test, the first keeps all.
+pub const CROPS: [f64; 5] = [1.0, 0.99, 0.95, 0.9, 0.75];
+
+/// Running mean and variance (Welford's algorithm).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Moments {
+    pub n: f64,
+    pub mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    pub fn push(&mut self, x: f64) {
+        self.n += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.n;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.m2 / (self.n - 1.0)
+    }
+}
+
+/// Welch's t statistic of the difference of means of two samples.
+pub fn welch_t(a: &Moments, b: &Moments) -> f64 {
+    (a.mean - b.mean) / (a.variance() / a.n + b.variance() / b.n).sqrt()
+}
+
+#[derive(Clone, Debug)]
+pub struct TimingResult {
+    pub samples: usize,
+    /// (fraction of measurements kept, t statistic) for every one of [`CROPS`]
+    pub tests: Vec<(f64, f64)>,
+}
+
+impl TimingResult {
+    /// Largest |t| of all tests, NaN if any of them is undefined.
+    pub fn max_t(&self) -> f64 {
+        self.tests
+            .iter()
+            .map(|(_, t)| t.abs())
+            .fold(0.0, |max: f64, t| {
+                if max.is_nan() || t.is_nan() {
+                    f64::NAN
+                } else {
+                    max.max(t)
+                }
+            })
+    }
+
+    /// Also true if some t statistic is undefined, which is no evidence of the lack of a leak.
+    pub fn leaks(&self) -> bool {
+        let t = self.max_t();
+        t.is_nan() || t > T_THRESHOLD
+    }
+}
+
+/// t statistics of measurements split into the fixed (`true`) and random classes,
+/// returns an error if any test would have fewer than 2 measurements in a class.
+pub fn t_tests(measurements: &[(bool, f64)]) -> Result<Vec<(f64, f64)>, String> {
+    let mut sorted = measurements.iter().map(|&(_, t)| t).collect::<Vec<f64>>();
+    sorted.sort_by(f64::total_cmp);
+    CROPS
+        .iter()
+        .map(|&crop| {
+            let limit = sorted
+                .get(((sorted.len() as f64 * crop) as usize).min(sorted.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or(f64::INFINITY);
+            let mut classes = [Moments::default(); 2];
+            for &(fixed, time) in measurements.iter().filter(|&&(_, t)| t <= limit) {
+                classes[fixed as usize].push(time);
+            }
+            if classes.iter().any(|c| c.n < 2.0) {
+                return Err(format!(
+                    "too few measurements, both classes need at least 2 of the fastest {}%",
+                    crop * 100.0
+                ));
+            }
+            Ok((crop, welch_t(&classes[1], &classes[0])))
+        })
+        .collect()
+}
+
+/// Measures `samples` single-block encryptions, each of the fixed `input` or of a random
+/// plaintext with equal probability.
+pub fn timing_test<R: Rng>(
+    cipher: &DNAC,
+    rng: &mut R,
+    input: &[DNA; INPUT_SIZE],
+    samples: usize,
+) -> Result<TimingResult, String> {
+    // inputs are prepared up front in the same kind of buffers, so that only
+    // the encryption itself can differ
+    let inputs = (0..samples)
+        .map(|_| match rng.gen::<bool>() {
+            true => (true, *input),
+            false => (false, random_dna(rng, INPUT_SIZE / 4).try_into().unwrap()),
+        })
+        .collect::<Vec<(bool, [DNA; INPUT_SIZE])>>();
+    let measurements = inputs
+        .iter()
+        .map(|(fixed, input)| {
+            let start = Instant::now();
+            black_box(cipher.encrypt(black_box(input).to_vec()));
+            (*fixed, start.elapsed().as_nanos() as f64)
+        })
+        .collect::<Vec<(bool, f64)>>();
+    Ok(TimingResult {
+        samples,
+        tests: t_tests(&measurements)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_t_tests() {
+        // both classes with the same times
+        let measurements = (0..20000)
+            .map(|i| (i % 2 == 0, 100.0 + ((i / 2) % 7) as f64))
+            .collect::<Vec<(bool, f64)>>();
+        let same = t_tests(&measurements).unwrap();
+        assert!(same.iter().all(|(_, t)| t.abs() < T_THRESHOLD));
+
+        let shifted = measurements
+            .iter()
+            .map(|&(fixed, t)| (fixed, if fixed { t + 0.5 } else { t }))
+            .collect::<Vec<(bool, f64)>>();
+        let result = TimingResult {
+            samples: shifted.len(),
+            tests: t_tests(&shifted).unwrap(),
+        };
+        assert!(result.leaks());
+        assert_eq!(result.tests.len(), CROPS.len());
+
+        assert!(t_tests(&[]).is_err());
+        assert!(t_tests(&measurements[..3]).is_err());
+        let undefined = TimingResult {
+            samples: 0,
+            tests: vec![(1.0, f64::NAN), (0.9, 0.0)],
+        };
+        assert!(undefined.max_t().is_nan());
+        assert!(undefined.leaks());
+    }
+}
@@ -0,0 +1,849 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use dnac::analysis::{
+    cipher::cipher_by_name,
+    cycles::{compare_random, cycle_structure, random_cycles, random_longest, Variant},
+    differential::{output_differences, search_characteristics, State},
+    diffusion::{diffusion_curve, full_avalanche, Flip, Target},
+    dna_to_hex,
+    introns::intron_statistics,
+    key_schedule::{
+        distinct, period, related_key_diffusion, round_keys, shift_similarity, slid_pairs,
+        structured_keys,
+    },
+    linear::{best_approximation, best_sbox_approximations, candidate_masks, noise_level, Mask},
+    parse_bases, parse_hex, random_blocks, random_dna,
+    report::{run_suite, Report, Samples},
+    sac::{base_change_probabilities, base_sac_matrix, base_sac_result},
+    timing::{timing_test, T_THRESHOLD},
+    BlockDNAC,
+};
+use dnac::{
+    dna::{binary_to_DNA, DNA},
+    key_schedule::{schedule_by_name, KeySchedule},
+    trace::bases,
+    DNAC,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{fs, io};
+
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+enum Commands {
+    /// Distribution of intron lengths and matched patterns per round
+    Introns {
+        #[arg(short, long, default_value_t = 22)]
+        rounds: usize,
+        #[arg(short, long, default_value_t = 1024)]
+        keys: usize,
+        #[arg(short, long, default_value_t = 256)]
+        texts: usize,
+        /// key size in bytes
+        #[arg(long, default_value_t = 16)]
+        key_size: usize,
+        /// flag keys with mean intron length this many standard deviations below average
+        #[arg(short, long, default_value_t = 3.0)]
+        z: f64,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Run SAC, base SAC, collision, coverage and linear span tests for a range of round counts
+    Sweep {
+        #[arg(long, default_value_t = 1)]
+        from: usize,
+        #[arg(long, default_value_t = 22)]
+        to: usize,
+        #[arg(long, default_value_t = 1, value_parser = positive)]
+        step: usize,
+        /// key schedule: aes, ctr, sponge
+        #[arg(long, default_value = "aes")]
+        schedule: String,
+        #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        #[arg(short, long)]
+        output: Option<String>,
+        #[arg(short, long)]
+        seed: Option<u64>,
+        #[command(flatten)]
+        samples: SampleArgs,
+    },
+    /// Run SAC, base SAC, collision, coverage and linear span tests for several ciphers
+    Compare {
+        /// ciphers to test: dnac, dnac-ctr, dnac-sponge, aes128, aes256, toy
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            default_value = "dnac,aes128,aes256,toy"
+        )]
+        ciphers: Vec<String>,
+        /// number of rounds of DNAC
+        #[arg(short, long, default_value_t = 22)]
+        rounds: usize,
+        #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        #[arg(short, long)]
+        output: Option<String>,
+        #[arg(short, long)]
+        seed: Option<u64>,
+        #[command(flatten)]
+        samples: SampleArgs,
+    },
+    /// Change probability of every output base after replacing single input bases
+    BaseSac {
+        /// cipher to test: dnac, dnac-ctr, dnac-sponge, aes128, aes256, toy
+        #[arg(short, long, default_value = "dnac")]
+        cipher: String,
+        /// number of rounds of DNAC
+        #[arg(short, long, default_value_t = 22)]
+        rounds: usize,
+        /// number of random inputs
+        #[arg(short = 'n', long, default_value_t = Samples::default().base_sac, value_parser = positive)]
+        samples: usize,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Output difference frequencies and differential characteristics of reduced-round DNAC
+    Differential {
+        #[command(flatten)]
+        difference: DifferenceArgs,
+        #[arg(short, long, default_value_t = 4)]
+        rounds: usize,
+        /// number of plaintext pairs encrypted with the whole reduced-round cipher
+        #[arg(short, long, default_value_t = 1 << 16)]
+        pairs: usize,
+        /// number of most frequent output differences to print
+        #[arg(short, long, default_value_t = 10)]
+        top: usize,
+        /// number of pairs used to estimate probability of a single round
+        #[arg(long, default_value_t = 1 << 12)]
+        round_pairs: usize,
+        /// number of characteristics kept after each round of the search
+        #[arg(short, long, default_value_t = 8, value_parser = positive)]
+        beam: usize,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Bias of linear approximations of reduced-round DNAC
+    Linear {
+        #[arg(long, default_value_t = 1)]
+        from: usize,
+        #[arg(long, default_value_t = 8)]
+        to: usize,
+        #[arg(short = 'n', long, default_value_t = 1 << 16)]
+        samples: usize,
+        /// number of best S-box approximations used to build candidate masks
+        #[arg(short, long, default_value_t = 4)]
+        top: usize,
+        /// input mask as 32 hex digits, tested instead of the candidates from the S-box LAT
+        #[arg(long, requires = "output_mask")]
+        input_mask: Option<String>,
+        /// output mask as 32 hex digits
+        #[arg(long, requires = "input_mask")]
+        output_mask: Option<String>,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Slid pairs, related-key diffusion and repetitions of round keys
+    KeySchedule {
+        #[arg(short, long, default_value_t = 22)]
+        rounds: usize,
+        /// key size in bytes
+        #[arg(long, default_value_t = 16)]
+        key_size: usize,
+        /// number of random keys
+        #[arg(short, long, default_value_t = 64)]
+        keys: usize,
+        /// report round keys differing in at most this many bases as slid pairs
+        #[arg(short, long, default_value_t = 8)]
+        max_distance: usize,
+        /// key schedule: aes, ctr, sponge
+        #[arg(long, default_value = "aes")]
+        schedule: String,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Cycle structure of the whole permutation of scaled-down DNAC compared with random permutations
+    Cycles {
+        /// source block size in bases
+        #[arg(long, default_value_t = 4)]
+        source: usize,
+        /// target block size in bases, multiple of 4
+        #[arg(long, default_value_t = 4)]
+        target: usize,
+        #[arg(long, default_value_t = 4)]
+        intron: usize,
+        /// number of intron patterns in round keys
+        #[arg(long, default_value_t = 2)]
+        patterns: usize,
+        #[arg(short, long, default_value_t = 8)]
+        rounds: usize,
+        /// number of random keys
+        #[arg(short, long, default_value_t = 100, value_parser = positive)]
+        keys: usize,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Distances between states after every round for single-bit and single-base changes
+    Diffusion {
+        #[arg(short, long, default_value_t = 22)]
+        rounds: usize,
+        /// number of pairs of inputs for every kind of change
+        #[arg(short = 'n', long, default_value_t = 4096)]
+        samples: usize,
+        /// key size in bytes
+        #[arg(long, default_value_t = 16)]
+        key_size: usize,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// dudect-style test of timing differences between a fixed and random plaintexts
+    Timing {
+        #[arg(short, long, default_value_t = 22)]
+        rounds: usize,
+        /// number of measured encryptions
+        #[arg(short = 'n', long, default_value_t = 100000)]
+        samples: usize,
+        /// key size in bytes
+        #[arg(long, default_value_t = 16)]
+        key_size: usize,
+        /// fixed plaintext as bases, all A's by default
+        #[arg(long)]
+        fixed: Option<String>,
+        /// use the constant-time round function
+        #[arg(long)]
+        constant_time: bool,
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+struct DifferenceArgs {
+    /// input difference as hex bytes (binary xor)
+    #[arg(long, conflicts_with = "bases", required_unless_present = "bases")]
+    xor: Option<String>,
+    /// input difference as bases (dnaxor_1)
+    #[arg(long)]
+    bases: Option<String>,
+}
+
+impl DifferenceArgs {
+    fn state(&self) -> Result<State, String> {
+        let dna = match (&self.xor, &self.bases) {
+            (Some(hex), _) => parse_hex(hex)?
+                .iter()
+                .flat_map(binary_to_DNA)
+                .collect::<Vec<DNA>>(),
+            (_, Some(bases)) => parse_bases(bases)?,
+            _ => Vec::new(),
+        };
+        let mut state = [DNA::A; 64];
+        if dna.len() > state.len() {
+            return Err(format!("difference longer than {} bases", state.len()));
+        }
+        state[..dna.len()].copy_from_slice(&dna);
+        if state.iter().all(|&b| b == DNA::A) {
+            return Err("input difference must not be zero".to_string());
+        }
+        Ok(state)
+    }
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+struct SampleArgs {
+    #[arg(long, default_value_t = Samples::default().sac)]
+    sac_samples: usize,
+    #[arg(long, default_value_t = Samples::default().base_sac)]
+    base_sac_samples: usize,
+    #[arg(long, default_value_t = Samples::default().collision)]
+    collision_samples: usize,
+    #[arg(long, default_value_t = Samples::default().coverage)]
+    coverage_samples: usize,
+    #[arg(long, default_value_t = Samples::default().linspan)]
+    linspan_samples: usize,
+}
+
+impl From<SampleArgs> for Samples {
+    fn from(args: SampleArgs) -> Self {
+        Samples {
+            sac: args.sac_samples,
+            base_sac: args.base_sac_samples,
+            collision: args.collision_samples,
+            coverage: args.coverage_samples,
+            linspan: args.linspan_samples,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Markdown,
+}
+
+// counts which must not be zero
+fn positive(arg: &str) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(0) => Err("should be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+fn write_report(report: &Report, format: Format, output: Option<String>) -> io::Result<()> {
+    let text = match format {
+        Format::Csv => report.to_csv(),
+        Format::Markdown => report.to_markdown(),
+    };
+    match output {
+        Some(file) => fs::write(file, text),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+fn introns(
+    rounds: usize,
+    keys: usize,
+    texts: usize,
+    key_size: usize,
+    z: f64,
+    mut rng: StdRng,
+) -> Result<(), String> {
+    let stats = intron_statistics(&mut rng, keys, texts, key_size, rounds)?;
+
+    println!("round,mean_intron_len,no_intron_rate,mean_matches");
+    for round in 0..rounds {
+        println!(
+            "{},{:.4},{:.4},{:.4}",
+            round,
+            stats.mean_intron_len(round),
+            stats.no_intron_rate(round),
+            stats.mean_matches(round)
+        );
+    }
+
+    let weak = stats.weak_key_candidates(z);
+    println!();
+    println!("{} weak key candidates (z = {})", weak.len(), z);
+    for key in weak {
+        println!(
+            "{} mean_intron_len = {:.4}",
+            dna_to_hex(&key.key),
+            key.mean()
+        );
+    }
+    Ok(())
+}
+
+fn sweep(
+    rounds: impl Iterator<Item = usize>,
+    schedule: &dyn KeySchedule,
+    samples: Samples,
+    mut rng: StdRng,
+    format: Format,
+    output: Option<String>,
+) -> io::Result<()> {
+    let key = rng.gen::<[u8; 32]>();
+    let mut report = Report::new("rounds");
+    let mut secure = None;
+    for rounds in rounds {
+        eprintln!("testing {} rounds", rounds);
+        let cipher = BlockDNAC::with_schedule(&key, rounds, schedule)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let results = run_suite(&cipher, &mut rng, &samples);
+        match (results.iter().all(|r| r.passed), secure) {
+            (true, None) => secure = Some(rounds),
+            (false, _) => secure = None,
+            _ => (),
+        }
+        report.add(rounds.to_string(), results);
+    }
+    write_report(&report, format, output)?;
+    match secure {
+        Some(rounds) => eprintln!("all tests pass from {} rounds", rounds),
+        None => eprintln!("tests do not pass for the largest tested round count"),
+    }
+    Ok(())
+}
+
+fn compare(
+    ciphers: Vec<String>,
+    rounds: usize,
+    samples: Samples,
+    mut rng: StdRng,
+    format: Format,
+    output: Option<String>,
+) -> io::Result<()> {
+    let key = rng.gen::<[u8; 32]>();
+    let mut report = Report::new("cipher");
+    for name in ciphers {
+        let cipher = cipher_by_name(&name, &key, rounds)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        eprintln!("testing {}", cipher.name());
+        let results = run_suite(cipher.as_ref(), &mut rng, &samples);
+        report.add(cipher.name(), results);
+    }
+    write_report(&report, format, output)
+}
+
+fn base_sac(name: &str, rounds: usize, samples: usize, mut rng: StdRng) -> Result<(), String> {
+    let key = rng.gen::<[u8; 32]>();
+    let cipher = cipher_by_name(name, &key, rounds)?;
+    let texts = random_blocks(&mut rng, samples);
+    let matrix = base_sac_matrix(cipher.as_ref(), &texts);
+    println!("output base,change probability");
+    for (i, p) in base_change_probabilities(&matrix, samples)
+        .iter()
+        .enumerate()
+    {
+        println!("{},{:.4}", i, p);
+    }
+    let result = base_sac_result(&matrix, samples);
+    println!();
+    println!(
+        "{}: chi^2 = {:.3}, p-value = {:.6}, {}",
+        cipher.name(),
+        result.statistic,
+        result.p_value,
+        if result.passed { "passed" } else { "failed" }
+    );
+    Ok(())
+}
+
+fn format_difference(d: &State) -> String {
+    format!("{} ({})", dna_to_hex(d), bases(d))
+}
+
+struct DifferentialArgs {
+    input: State,
+    rounds: usize,
+    pairs: usize,
+    top: usize,
+    round_pairs: usize,
+    beam: usize,
+}
+
+fn differential(args: DifferentialArgs, mut rng: StdRng) {
+    let cipher = DNAC::new(random_dna(&mut rng, 32), args.rounds);
+    let table = output_differences(&cipher, &args.input, args.pairs, &mut rng);
+
+    println!("input difference {}", format_difference(&args.input));
+    println!();
+    println!("output difference,count,probability,log2 probability");
+    for (d, count) in table.top(args.top) {
+        let p = table.probability(count);
+        println!(
+            "{},{},{:.6},{:.2}",
+            format_difference(&d),
+            count,
+            p,
+            p.log2()
+        );
+    }
+    println!();
+    println!("active bases,count");
+    for (active, count) in table.active_bases().iter().enumerate() {
+        if *count > 0 {
+            println!("{},{}", active, count);
+        }
+    }
+
+    println!();
+    println!("rounds,log2 probability,characteristic");
+    let best = search_characteristics(
+        &args.input,
+        args.rounds,
+        args.round_pairs,
+        args.beam,
+        &mut rng,
+    );
+    for characteristic in best {
+        println!(
+            "{},{:.2},{}",
+            characteristic.rounds(),
+            characteristic.log2_probability(),
+            characteristic
+                .differences
+                .iter()
+                .map(|d| bases(d))
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        );
+    }
+}
+
+fn parse_mask(mask: &str) -> Result<Mask, String> {
+    u128::from_str_radix(mask, 16).map_err(|e| format!("{}: {}", mask, e))
+}
+
+fn linear(
+    rounds: impl Iterator<Item = usize>,
+    samples: usize,
+    top: usize,
+    masks: Vec<(Mask, Mask)>,
+    mut rng: StdRng,
+) {
+    println!("s-box approximations (input mask,output mask,bias)");
+    for (a, b, bias) in best_sbox_approximations(top) {
+        println!("{:02x},{:02x},{:.4}", a, b, bias);
+    }
+    println!();
+    // largest bias expected by chance among all candidates
+    let noise = noise_level(masks.len(), samples);
+    println!(
+        "{} candidate approximations, noise level {:.6}",
+        masks.len(),
+        noise
+    );
+    println!("rounds,input mask,output mask,bias,ci low,ci high,log2 |bias|,above noise");
+    let key = rng.gen::<[u8; 32]>();
+    for rounds in rounds {
+        let cipher = BlockDNAC::new(&key, rounds).unwrap();
+        let best = best_approximation(&cipher, &masks, samples, &mut rng);
+        let (low, high) = best.interval();
+        println!(
+            "{},{:032x},{:032x},{:.6},{:.6},{:.6},{:.2},{}",
+            rounds,
+            best.input_mask,
+            best.output_mask,
+            best.bias,
+            low,
+            high,
+            best.bias.abs().log2(),
+            best.significant() && best.bias.abs() > noise
+        );
+    }
+}
+
+fn key_schedule(
+    schedule: &(dyn KeySchedule + Sync),
+    rounds: usize,
+    key_size: usize,
+    keys: usize,
+    max_distance: usize,
+    mut rng: StdRng,
+) -> Result<(), String> {
+    // 4 bases per byte
+    schedule.check_key_size(4 * key_size)?;
+    println!("key,distinct round keys,period,slid pairs,min distance");
+    for (name, key) in structured_keys(key_size) {
+        let round_keys = round_keys(schedule, &key, rounds);
+        let pairs = slid_pairs(&round_keys, max_distance);
+        println!(
+            "{},{},{},{},{}",
+            name,
+            distinct(&round_keys),
+            period(&round_keys).map_or("-".to_string(), |p| p.to_string()),
+            pairs.len(),
+            pairs
+                .iter()
+                .map(|p| p.distance)
+                .min()
+                .map_or("-".to_string(), |d| d.to_string())
+        );
+    }
+
+    println!();
+    println!("shift,mean equal bases (random keys),max equal bases (random keys)");
+    let similarities = (0..keys)
+        .map(|_| {
+            let key = (0..key_size).map(|_| rng.gen()).collect::<Vec<u8>>();
+            shift_similarity(&round_keys(schedule, &key, rounds))
+        })
+        .collect::<Vec<Vec<f64>>>();
+    for shift in 1..rounds {
+        let values = similarities.iter().map(|s| s[shift - 1]);
+        println!(
+            "{},{:.4},{:.4}",
+            shift,
+            values.clone().sum::<f64>() / keys as f64,
+            values.fold(0.0, f64::max)
+        );
+    }
+
+    println!();
+    println!("round key,mean changed bases,min changed bases");
+    for (round, (mean, min)) in related_key_diffusion(schedule, &mut rng, keys, key_size, rounds)
+        .iter()
+        .enumerate()
+    {
+        println!("{},{:.4},{:.4}", round, mean, min);
+    }
+    Ok(())
+}
+
+fn cycles(variant: Variant, rounds: usize, keys: usize, mut rng: StdRng) -> Result<(), String> {
+    println!("key,cycles,fixed points,longest cycle");
+    let mut stats = Vec::new();
+    for key in 0..keys {
+        let round_keys = variant.random_round_keys(&mut rng, rounds);
+        let s = cycle_structure(&variant.permutation(&round_keys)?)?;
+        println!(
+            "{},{},{},{}",
+            key,
+            s.cycles(),
+            s.fixed_points(),
+            s.longest()
+        );
+        stats.push(s);
+    }
+
+    let states = 1usize << (2 * variant.block_size());
+    println!();
+    println!(
+        "random permutation of {} states: {:.2} cycles, 1 fixed point, longest cycle {:.0}",
+        states,
+        random_cycles(states).0,
+        random_longest().0 * states as f64
+    );
+    println!("test,statistic,p-value,passed");
+    for result in compare_random(&stats) {
+        println!(
+            "{},{:.4},{:.4},{}",
+            result.name, result.statistic, result.p_value, result.passed
+        );
+    }
+    Ok(())
+}
+
+fn diffusion(
+    rounds: usize,
+    samples: usize,
+    key_size: usize,
+    mut rng: StdRng,
+) -> Result<(), String> {
+    let changes = [
+        ("plaintext", "bit", Target::Plaintext, Flip::Bit),
+        ("plaintext", "base", Target::Plaintext, Flip::Base),
+        ("key", "bit", Target::Key, Flip::Bit),
+        ("key", "base", Target::Key, Flip::Base),
+    ];
+    let mut avalanche = Vec::new();
+    println!("changed,change,rounds,differing bits,differing bases");
+    for (name, change_name, target, change) in changes {
+        let curve = diffusion_curve(&mut rng, samples, key_size, rounds, target, change)?;
+        for point in &curve {
+            println!(
+                "{},{},{},{:.4},{:.4}",
+                name, change_name, point.rounds, point.bits, point.bases
+            );
+        }
+        avalanche.push((name, change_name, full_avalanche(&curve, samples)));
+    }
+
+    println!();
+    println!("changed,change,full avalanche after rounds");
+    for (name, change_name, rounds) in avalanche {
+        println!(
+            "{},{},{}",
+            name,
+            change_name,
+            rounds.map_or("-".to_string(), |r| r.to_string())
+        );
+    }
+    Ok(())
+}
+
+fn timing(
+    rounds: usize,
+    samples: usize,
+    key_size: usize,
+    fixed: [DNA; 64],
+    constant_time: bool,
+    mut rng: StdRng,
+) -> Result<(), String> {
+    let cipher = DNAC::try_new(random_dna(&mut rng, key_size), rounds)?;
+    let cipher = if constant_time {
+        cipher.constant_time()
+    } else {
+        cipher
+    };
+    let result = timing_test(&cipher, &mut rng, &fixed, samples)?;
+    println!("kept,t");
+    for (crop, t) in &result.tests {
+        println!("{:.2},{:.3}", crop, t);
+    }
+    println!();
+    println!(
+        "max |t| = {:.3} over {} measurements: {}",
+        result.max_t(),
+        result.samples,
+        if result.max_t().is_nan() {
+            "undefined t statistic".to_string()
+        } else if result.leaks() {
+            format!("timing leak (above {})", T_THRESHOLD)
+        } else {
+            "no leak detected".to_string()
+        }
+    );
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let args = Args::parse();
+
+    match args.command {
+        Commands::Introns {
+            rounds,
+            keys,
+            texts,
+            key_size,
+            z,
+            seed,
+        } => introns(rounds, keys, texts, key_size, z, rng(seed))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+        Commands::Sweep {
+            from,
+            to,
+            step,
+            schedule,
+            format,
+            output,
+            seed,
+            samples,
+        } => sweep(
+            (from..=to).step_by(step),
+            schedule_by_name(&schedule)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+                .as_ref(),
+            samples.into(),
+            rng(seed),
+            format,
+            output,
+        ),
+        Commands::Compare {
+            ciphers,
+            rounds,
+            format,
+            output,
+            seed,
+            samples,
+        } => compare(ciphers, rounds, samples.into(), rng(seed), format, output),
+        Commands::BaseSac {
+            cipher,
+            rounds,
+            samples,
+            seed,
+        } => base_sac(&cipher, rounds, samples, rng(seed))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+        Commands::Differential {
+            difference,
+            rounds,
+            pairs,
+            top,
+            round_pairs,
+            beam,
+            seed,
+        } => {
+            let input = difference
+                .state()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let args = DifferentialArgs {
+                input,
+                rounds,
+                pairs,
+                top,
+                round_pairs,
+                beam,
+            };
+            differential(args, rng(seed));
+            Ok(())
+        }
+        Commands::Linear {
+            from,
+            to,
+            samples,
+            top,
+            input_mask,
+            output_mask,
+            seed,
+        } => {
+            let masks = match (input_mask, output_mask) {
+                (Some(input), Some(output)) => vec![(
+                    parse_mask(&input)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+                    parse_mask(&output)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+                )],
+                _ => candidate_masks(top),
+            };
+            linear(from..=to, samples, top, masks, rng(seed));
+            Ok(())
+        }
+        Commands::KeySchedule {
+            rounds,
+            key_size,
+            keys,
+            max_distance,
+            schedule,
+            seed,
+        } => {
+            let schedule = schedule_by_name(&schedule)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            key_schedule(
+                schedule.as_ref(),
+                rounds,
+                key_size,
+                keys,
+                max_distance,
+                rng(seed),
+            )
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+        }
+        Commands::Cycles {
+            source,
+            target,
+            intron,
+            patterns,
+            rounds,
+            keys,
+            seed,
+        } => Variant::new(source, target, intron, patterns)
+            .and_then(|variant| cycles(variant, rounds, keys, rng(seed)))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+        Commands::Diffusion {
+            rounds,
+            samples,
+            key_size,
+            seed,
+        } => diffusion(rounds, samples, key_size, rng(seed))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+        Commands::Timing {
+            rounds,
+            samples,
+            key_size,
+            fixed,
+            constant_time,
+            seed,
+        } => {
+            let fixed = match fixed {
+                Some(bases) => parse_bases(&bases)
+                    .and_then(|bases| {
+                        bases
+                            .try_into()
+                            .map_err(|_| "fixed plaintext should have 64 bases".to_string())
+                    })
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+                None => [DNA::A; 64],
+            };
+            timing(rounds, samples, key_size, fixed, constant_time, rng(seed))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+        }
+    }
+}
@@ -1,12 +1,16 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use log::{debug, error};
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::io::BufReader;
 use std::io::{stdin, stdout};
 use std::io::{Read, Write};
 
-use dnac::{dna, dna::DNA, DNAC};
+use dnac::{dna, dna::DNA, kat, mct, trace, DEFAULT_ROUNDS, DNAC};
+
+// vectors checked by `dnac selftest` unless other files are given
+const KAT_VECTORS: &str = include_str!("../../tests/data/kat.rsp");
+const MCT_VECTORS: &str = include_str!("../../tests/data/mct.rsp");
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
@@ -19,6 +23,9 @@ struct Args {
     key: Option<String>,
     #[arg(short, long, default_value_t = 0)]
     verbose: usize,
+    /// Use the constant-time round function
+    #[arg(long)]
+    constant_time: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -27,6 +34,35 @@ struct Args {
 enum Commands {
     Encrypt,
     Decrypt,
+    /// Encrypt input and print state of every round
+    Trace {
+        #[arg(short, long, value_enum, default_value_t = TraceFormat::Table)]
+        format: TraceFormat,
+    },
+    /// Check the build against the known answer tests
+    Selftest {
+        /// Also run the Monte Carlo tests
+        #[arg(long)]
+        mct: bool,
+        /// Known answer tests, as generated by `dnac kat`, instead of the built-in ones
+        #[arg(long)]
+        vectors: Option<String>,
+        /// Monte Carlo tests, as generated by `dnac kat --mct`, instead of the built-in ones
+        #[arg(long)]
+        mct_vectors: Option<String>,
+    },
+    /// Generate known answer tests with the current implementation
+    Kat {
+        /// Generate Monte Carlo tests instead
+        #[arg(long)]
+        mct: bool,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum TraceFormat {
+    Table,
+    Json,
 }
 
 fn read_file<T: Read>(mut reader: T) -> io::Result<Vec<DNA>> {
@@ -59,6 +95,45 @@ fn main() -> io::Result<()> {
         .init()
         .unwrap();
 
+    match args.command {
+        Commands::Selftest {
+            mct,
+            vectors,
+            mct_vectors,
+        } => {
+            let vectors = match vectors {
+                Some(file) => fs::read_to_string(file)?,
+                None => KAT_VECTORS.to_string(),
+            };
+            let count = kat::selftest(&vectors)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            println!("{} known answer tests passed", count);
+            if mct {
+                let vectors = match mct_vectors {
+                    Some(file) => fs::read_to_string(file)?,
+                    None => MCT_VECTORS.to_string(),
+                };
+                let count = mct::selftest(&vectors)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                println!("{} Monte Carlo tests passed", count);
+            }
+            return Ok(());
+        }
+        Commands::Kat { mct } => {
+            let text = if mct {
+                mct::generate().and_then(|records| mct::render(&records).map_err(|e| e.to_string()))
+            } else {
+                kat::generate().and_then(|vectors| kat::render(&vectors).map_err(|e| e.to_string()))
+            }
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            return match args.output {
+                Some(file) => File::create(file)?.write_all(text.as_bytes()),
+                None => stdout().write_all(text.as_bytes()),
+            };
+        }
+        _ => {}
+    }
+
     let dna = match args.input {
         Some(file) => read_file(BufReader::new(File::open(file)?))?,
         None => read_file(stdin())?,
@@ -71,12 +146,31 @@ fn main() -> io::Result<()> {
     debug!("key = {:?}", key);
     debug!("msg = {:?}", dna);
 
-    let cipher = DNAC::new_default(key);
-
-    let result = if args.command == Commands::Encrypt {
-        Ok(cipher.encrypt(dna))
+    let cipher = DNAC::try_new(key, DEFAULT_ROUNDS)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let cipher = if args.constant_time {
+        cipher.constant_time()
     } else {
-        cipher.decrypt(dna)
+        cipher
+    };
+
+    let result = match args.command {
+        Commands::Encrypt => Ok(cipher.encrypt(dna)),
+        Commands::Decrypt => cipher.decrypt(dna),
+        Commands::Trace { format } => {
+            let mut recorder = trace::TraceRecorder::default();
+            cipher.encrypt_observed(dna, &mut recorder);
+            let report = match format {
+                TraceFormat::Table => trace::render_table(&recorder.rounds),
+                TraceFormat::Json => trace::render_json(&recorder.rounds),
+            };
+            match args.output {
+                Some(file) => File::create(file)?.write_all(report.as_bytes()),
+                None => stdout().write_all(report.as_bytes()),
+            }?;
+            return Ok(());
+        }
+        Commands::Selftest { .. } | Commands::Kat { .. } => unreachable!(),
     };
 
     match result {
@@ -85,7 +179,11 @@ fn main() -> io::Result<()> {
 
             match args.output {
                 Some(file) => write_file(
-                    OpenOptions::new().write(true).create(true).open(file)?,
+                    OpenOptions::new()
+                        .write(true)
+                        .create(true)
+                        .truncate(true)
+                        .open(file)?,
                     result,
                 ),
                 None => write_file(stdout(), result),
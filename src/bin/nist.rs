@@ -1,27 +1,70 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use dnac::{
+    analysis::nist::{bits_from_bytes, run_tests, FinalReport, Parameters, ParsedReport, Summary},
     bits::{BitsOne, BitsTwo},
     dna::{self, DNA},
     DNAC,
 };
 use kdam::tqdm;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+    ParallelSlice,
+};
 use std::{
     collections::HashMap,
-    fmt::Display,
-    fs::{File, OpenOptions},
-    io::{self, BufReader, Read, Write},
+    fs::{self, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
 };
 
 const INPUT_SIZE_BYTES: usize = 16;
-const INPUT_SIZE_DNA: usize = 64;
-const RAND_FILE: &str = "/dev/urandom";
-// const ZERO_FILE: &str = "/dev/zero";
+const STDOUT: &str = "-";
+const BLOCK_BITS: usize = INPUT_SIZE_BYTES * 8;
+// number of plaintexts encrypted with every key in `correlation` and `random`
+const TEXTS_PER_KEY: usize = 8128;
+// number of blocks in every chain in `block_chaining`
+const CHAIN_LENGTH: usize = 8192;
+
+// writes at least `config.blocks()` blocks of the dataset to the output,
+// `position` is the line of its progress bar
+type Generator = fn(&mut dyn Write, &mut StdRng, &Config, u16) -> io::Result<()>;
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    /// output file for a single dataset, `-` for stdout,
+    /// `result` with the extension of the format by default
     #[arg(short, long)]
     output: Option<String>,
+    /// run the SP 800-22 tests on the generated data and write the final analysis report
+    #[arg(short, long)]
+    report: bool,
+    /// directory of the datasets relative to the directory `assess` is run from,
+    /// used in the answers written for it
+    #[arg(long, default_value = "dnac")]
+    assess_dir: String,
+    /// seed of the generator of keys, plaintexts and IVs, random if not given
+    #[arg(short, long)]
+    seed: Option<u64>,
+    /// generate all datasets concurrently instead of one after another
+    #[arg(short, long)]
+    all: bool,
+    /// number of sequences, depends on the dataset if not given
+    #[arg(short = 'n', long)]
+    sequences: Option<usize>,
+    /// length of a single sequence in bits, depends on the dataset if not given
+    #[arg(short, long)]
+    length: Option<usize>,
+    /// size of keys in bytes (16 or 32)
+    #[arg(short, long, default_value_t = 16)]
+    key_size: usize,
+    /// number of cipher rounds
+    #[arg(long, default_value_t = 22)]
+    rounds: usize,
+    /// format of the output
+    #[arg(short, long, value_enum, default_value_t = Format::Binary)]
+    format: Format,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -37,231 +80,577 @@ enum Commands {
     PlaintextLD,
     KeyHD,
     PlaintextHD,
+    /// Run the SP 800-22 tests on an existing file
+    Assess {
+        input: String,
+        /// length of a single sequence in bits
+        #[arg(short, long, default_value_t = 1048576)]
+        length: usize,
+    },
+    /// Summarize final analysis reports of several datasets in a single table
+    Summary {
+        #[arg(required = true)]
+        reports: Vec<String>,
+    },
 }
 
-impl Display for Commands {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Commands {
+    fn default_sequences(&self) -> usize {
+        match self {
+            Commands::KeyAv | Commands::PlaintextAv => 384,
+            Commands::BlockChaining => 300,
+            _ => 128,
+        }
+    }
+
+    // density datasets have one sequence for every key or plaintext
+    fn default_length(&self, key_size: usize) -> usize {
         match self {
-            Commands::KeyAv => write!(f, "key_av.blb"),
-            Commands::PlaintextAv => write!(f, "plaintext_av.blb"),
-            Commands::Correlation => write!(f, "correlation.blb"),
-            Commands::BlockChaining => write!(f, "block_chaining.blb"),
-            Commands::Random => write!(f, "random.blb"),
-            Commands::KeyLD => write!(f, "key_low_density.blb"),
-            Commands::PlaintextLD => write!(f, "plaintext_low_density.blb"),
-            Commands::KeyHD => write!(f, "key_high_density.blb"),
-            Commands::PlaintextHD => write!(f, "plaintext_high_density.blb"),
+            Commands::KeyLD | Commands::KeyHD => density_size(key_size * 8) * BLOCK_BITS,
+            Commands::PlaintextLD | Commands::PlaintextHD => density_size(BLOCK_BITS) * BLOCK_BITS,
+            Commands::Assess { length, .. } => *length,
+            _ => 1048576,
         }
     }
 }
 
-fn write_block<T: Write>(mut writer: T, dna: &Vec<DNA>) -> io::Result<usize> {
-    let buffer = dna
-        .chunks_exact(4)
-        .map(|chunk| dna::DNA_to_binary(chunk.try_into().unwrap()))
-        .collect::<Vec<u8>>();
-    writer.write(&buffer)
+impl Commands {
+    // name of the dataset, also used for its default file name
+    fn name(&self) -> &str {
+        match self {
+            Commands::KeyAv => "key_av",
+            Commands::PlaintextAv => "plaintext_av",
+            Commands::Correlation => "correlation",
+            Commands::BlockChaining => "block_chaining",
+            Commands::Random => "random",
+            Commands::KeyLD => "key_low_density",
+            Commands::PlaintextLD => "plaintext_low_density",
+            Commands::KeyHD => "key_high_density",
+            Commands::PlaintextHD => "plaintext_high_density",
+            Commands::Assess { input, .. } => input,
+            Commands::Summary { .. } => "summary",
+        }
+    }
+
+    fn file_name(&self, format: Format) -> String {
+        format!("{}.{}", self.name(), format.extension())
+    }
 }
 
-fn u8_to_dna(input: [u8; INPUT_SIZE_BYTES]) -> Vec<DNA> {
-    input.iter().flat_map(dna::binary_to_DNA).collect()
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// raw bytes
+    Binary,
+    /// characters '0' and '1', as read by `assess`
+    Ascii,
+    /// 32-bit integers with a header, as read by `dieharder -g 202`
+    Dieharder,
+    /// real numbers from [0, 1), as read by `ufile_CreateReadText` of TestU01
+    Testu01,
 }
 
-fn key_avalanche(output: File) -> io::Result<()> {
-    let mut keys = BufReader::new(File::open(RAND_FILE)?);
-    let mut buffer = [0; INPUT_SIZE_BYTES];
-    let input_zeros = vec![DNA::A; INPUT_SIZE_DNA];
-
-    for _ in tqdm!(0..24576) {
-        if let Ok(_) = keys.read_exact(&mut buffer) {
-            let key_0 = u8_to_dna(buffer);
-            let cipher_0 = DNAC::new_default(key_0);
-            let block_0 = cipher_0.encrypt(input_zeros.clone());
-            for key in BitsOne::new(buffer) {
-                let key = u8_to_dna(key);
-                let cipher = DNAC::new_default(key);
-                let block = cipher.encrypt(input_zeros.clone());
-                let result: Vec<DNA> = block_0.iter().zip(block).map(|(&a, b)| a ^ b).collect();
-                write_block(output.try_clone()?, &result)?;
-            }
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Binary => "blb",
+            Format::Ascii => "txt",
+            Format::Dieharder => "dh",
+            Format::Testu01 => "u01",
         }
     }
-    Ok(())
 }
 
-fn plaintext_avalanche(output: File) -> io::Result<()> {
-    let mut texts = BufReader::new(File::open(RAND_FILE)?);
-    let mut buffer = [0; INPUT_SIZE_BYTES];
-    let cipher = DNAC::new_default(vec![DNA::A; INPUT_SIZE_DNA]);
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    sequences: usize,
+    length: usize,
+    key_size: usize,
+    rounds: usize,
+    format: Format,
+}
 
-    for _ in tqdm!(0..24576) {
-        if let Ok(_) = texts.read_exact(&mut buffer) {
-            let text_0 = u8_to_dna(buffer);
-            let block_0 = cipher.encrypt(text_0);
-            for text in BitsOne::new(buffer) {
-                let text = u8_to_dna(text);
-                let block = cipher.encrypt(text);
-                let result = block_0.iter().zip(block).map(|(&a, b)| a ^ b).collect();
-                write_block(output.try_clone()?, &result)?;
-            }
+impl Config {
+    fn new(args: &Args, dataset: &Commands) -> io::Result<Config> {
+        let config = Config {
+            sequences: args.sequences.unwrap_or(dataset.default_sequences()),
+            length: args.length.unwrap_or(dataset.default_length(args.key_size)),
+            key_size: args.key_size,
+            rounds: args.rounds,
+            format: args.format,
+        };
+        let error = |message: &str| Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        if config.key_size != 16 && config.key_size != 32 {
+            return error("key size must be 16 or 32 bytes");
+        }
+        if config.sequences == 0 || config.length == 0 || !config.length.is_multiple_of(8) {
+            return error("sequence length must be a positive multiple of 8");
         }
+        Ok(config)
+    }
+
+    // number of blocks needed for all sequences
+    fn blocks(&self) -> usize {
+        (self.sequences * self.length).div_ceil(BLOCK_BITS)
+    }
+
+    // number of groups of `size` blocks needed for all sequences
+    fn groups(&self, size: usize) -> usize {
+        self.blocks().div_ceil(size)
+    }
+
+    fn cipher(&self, key: &[u8]) -> DNAC {
+        DNAC::new(u8_to_dna(key), self.rounds)
+    }
+
+    fn random_key(&self, rng: &mut StdRng) -> Vec<u8> {
+        random_bytes(rng, self.key_size)
     }
-    Ok(())
 }
 
-fn correlation(output: File) -> io::Result<()> {
-    const INPUT_BLOCKS_SIZE: usize = 130048; // 16 (one block in bytes) * 8128
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
-    let mut texts = [0; INPUT_BLOCKS_SIZE];
-    inputs.read_exact(&mut texts)?;
-
-    for _ in tqdm!(0..128) {
-        let mut key = [0; INPUT_SIZE_BYTES];
-        inputs.read_exact(&mut key)?;
-        let key = u8_to_dna(key);
-        let cipher = DNAC::new_default(key);
-        for text in texts.chunks_exact(INPUT_SIZE_BYTES) {
-            let text = u8_to_dna(text.try_into().unwrap());
-            let block = cipher.encrypt(text.clone());
-            let result = text.iter().zip(block).map(|(&a, b)| a ^ b).collect();
-            write_block(output.try_clone()?, &result)?;
-        }
+// discards everything written after the first `remaining` bytes,
+// so that generators can work on whole groups of blocks
+struct Truncated<'a> {
+    inner: &'a mut dyn Write,
+    remaining: usize,
+}
+
+impl Write for Truncated<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.remaining);
+        self.inner.write_all(&buf[..n])?;
+        self.remaining -= n;
+        Ok(buf.len())
     }
-    Ok(())
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// writes bytes in the given format, integers and reals are made of 4 consecutive bytes
+struct Encoder<'a> {
+    inner: &'a mut dyn Write,
+    format: Format,
+    pending: Vec<u8>,
 }
 
-fn block_chaining(output: File) -> io::Result<()> {
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
-    let text = vec![DNA::A; INPUT_SIZE_DNA];
-
-    for _ in tqdm!(0..300) {
-        let mut iv = [0; INPUT_SIZE_BYTES];
-        let mut key = [0; INPUT_SIZE_BYTES];
-        inputs.read_exact(&mut iv)?;
-        inputs.read_exact(&mut key)?;
-        let mut iv = u8_to_dna(iv);
-        let key = u8_to_dna(key);
-        let cipher = DNAC::new_default(key);
-        for _ in 0..8192 {
-            let input: Vec<DNA> = text.iter().zip(iv.clone()).map(|(&a, b)| a ^ b).collect();
-            iv = cipher.encrypt(input.clone());
-            write_block(output.try_clone()?, &iv)?;
+impl Write for Encoder<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.format {
+            Format::Binary => self.inner.write_all(buf)?,
+            Format::Ascii => {
+                let text = buf
+                    .iter()
+                    .flat_map(|&byte| (0..8).rev().map(move |i| b'0' + (byte >> i & 1)))
+                    .collect::<Vec<u8>>();
+                self.inner.write_all(&text)?;
+            }
+            Format::Dieharder | Format::Testu01 => {
+                self.pending.extend_from_slice(buf);
+                let words = self.pending.len() / 4 * 4;
+                for chunk in self.pending[..words].chunks_exact(4) {
+                    let word = u32::from_be_bytes(chunk.try_into().unwrap());
+                    if self.format == Format::Dieharder {
+                        writeln!(self.inner, "{}", word)?;
+                    } else {
+                        writeln!(self.inner, "{}", word as f64 / 4294967296.0)?;
+                    }
+                }
+                self.pending.drain(..words);
+            }
         }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
-    Ok(())
 }
 
-fn random(output: File) -> io::Result<()> {
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
-
-    for _ in tqdm!(0..128) {
-        let mut key = [0; INPUT_SIZE_BYTES];
-        inputs.read_exact(&mut key)?;
-        let key = u8_to_dna(key);
-        let cipher = DNAC::new_default(key);
-        for _ in 0..8128 {
-            let mut text = [0; INPUT_SIZE_BYTES];
-            inputs.read_exact(&mut text)?;
-            let text = u8_to_dna(text);
-            let block = cipher.encrypt(text.clone());
-            write_block(output.try_clone()?, &block)?;
-        }
+fn write_blocks(output: &mut dyn Write, blocks: &[Vec<DNA>]) -> io::Result<()> {
+    for block in blocks {
+        let buffer = block
+            .chunks_exact(4)
+            .map(|chunk| dna::DNA_to_binary(chunk.try_into().unwrap()))
+            .collect::<Vec<u8>>();
+        output.write_all(&buffer)?;
     }
     Ok(())
 }
 
-fn key_low_density(output: File) -> io::Result<()> {
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
+fn u8_to_dna(input: &[u8]) -> Vec<DNA> {
+    input.iter().flat_map(dna::binary_to_DNA).collect()
+}
+
+fn xor(a: &[DNA], b: &[DNA]) -> Vec<DNA> {
+    a.iter().zip(b).map(|(&a, &b)| a ^ b).collect()
+}
 
-    for _ in tqdm!(0..128) {
-        let keys = [[0; INPUT_SIZE_BYTES]]
-            .iter()
-            .map(|v| v.to_owned())
-            .chain(BitsOne::new([0; INPUT_SIZE_BYTES]))
-            .chain(BitsTwo::new([0; INPUT_SIZE_BYTES]));
+fn random_bytes(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let mut input = vec![0; size];
+    rng.fill_bytes(&mut input);
+    input
+}
 
-        for key in keys {
-            let key = u8_to_dna(key);
-            let cipher = DNAC::new_default(key);
+fn random_input(rng: &mut StdRng) -> Vec<u8> {
+    random_bytes(rng, INPUT_SIZE_BYTES)
+}
 
-            let mut text = [0; INPUT_SIZE_BYTES];
-            inputs.read_exact(&mut text)?;
-            let text = u8_to_dna(text);
-            let block = cipher.encrypt(text.clone());
-            write_block(output.try_clone()?, &block)?;
-        }
+// `base` followed by all inputs differing from it in one and in two bits
+fn density_inputs(base: Vec<u8>) -> Vec<Vec<u8>> {
+    [base.clone()]
+        .into_iter()
+        .chain(BitsOne::new(base.clone()))
+        .chain(BitsTwo::new(base))
+        .collect()
+}
+
+// number of inputs returned by `density_inputs` for `n` bits
+fn density_size(n: usize) -> usize {
+    1 + n + n * (n - 1) / 2
+}
+
+// Random inputs are always drawn from `rng` sequentially and blocks are encrypted in parallel,
+// so the output depends only on the seed.
+
+fn key_avalanche(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    let input_zeros = vec![DNA::A; INPUT_SIZE_BYTES * 4];
+
+    for _ in tqdm!(0..config.groups(config.key_size * 8), position = position) {
+        let key_0 = config.random_key(rng);
+        let block_0 = config.cipher(&key_0).encrypt(input_zeros.clone());
+        let blocks = BitsOne::new(key_0.clone())
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|key| xor(&block_0, &config.cipher(&key).encrypt(input_zeros.clone())))
+            .collect::<Vec<_>>();
+        write_blocks(output, &blocks)?;
     }
     Ok(())
 }
 
-fn plaintext_low_density(output: File) -> io::Result<()> {
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
-
-    for _ in tqdm!(0..128) {
-        let mut key = [0; INPUT_SIZE_BYTES];
-        inputs.read_exact(&mut key)?;
-        let key = u8_to_dna(key);
-        let cipher = DNAC::new_default(key);
+fn plaintext_avalanche(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    let cipher = config.cipher(&vec![0; config.key_size]);
+
+    for _ in tqdm!(0..config.groups(BLOCK_BITS), position = position) {
+        let text_0 = random_input(rng);
+        let block_0 = cipher.encrypt(u8_to_dna(&text_0));
+        let blocks = BitsOne::new(text_0.clone())
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|text| xor(&block_0, &cipher.encrypt(u8_to_dna(&text))))
+            .collect::<Vec<_>>();
+        write_blocks(output, &blocks)?;
+    }
+    Ok(())
+}
 
-        let texts = [[0; INPUT_SIZE_BYTES]]
-            .iter()
-            .map(|v| v.to_owned())
-            .chain(BitsOne::new([0; INPUT_SIZE_BYTES]))
-            .chain(BitsTwo::new([0; INPUT_SIZE_BYTES]));
+fn correlation(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    let texts = (0..TEXTS_PER_KEY)
+        .map(|_| random_input(rng))
+        .collect::<Vec<_>>();
+
+    for _ in tqdm!(0..config.groups(TEXTS_PER_KEY), position = position) {
+        let cipher = config.cipher(&config.random_key(rng));
+        let blocks = texts
+            .par_iter()
+            .map(|text| {
+                let text = u8_to_dna(text);
+                xor(&text, &cipher.encrypt(text.clone()))
+            })
+            .collect::<Vec<_>>();
+        write_blocks(output, &blocks)?;
+    }
+    Ok(())
+}
 
-        for text in texts {
-            let text = u8_to_dna(text);
-            let block = cipher.encrypt(text.clone());
-            write_block(output.try_clone()?, &block)?;
+fn block_chaining(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    // chains are sequential, so whole chains for several keys are computed in parallel
+    const KEYS_IN_BATCH: usize = 16;
+    let text = [DNA::A; INPUT_SIZE_BYTES * 4];
+    let inputs = (0..config.groups(CHAIN_LENGTH))
+        .map(|_| (random_input(rng), config.random_key(rng)))
+        .collect::<Vec<_>>();
+
+    for batch in tqdm!(inputs.chunks(KEYS_IN_BATCH), position = position) {
+        let chains = batch
+            .par_iter()
+            .map(|(iv, key)| {
+                let mut iv = u8_to_dna(iv);
+                let cipher = config.cipher(key);
+                (0..CHAIN_LENGTH)
+                    .map(|_| {
+                        iv = cipher.encrypt(xor(&text, &iv));
+                        iv.clone()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for chain in chains {
+            write_blocks(output, &chain)?;
         }
     }
     Ok(())
 }
 
-fn key_high_density(output: File) -> io::Result<()> {
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
-
-    for _ in tqdm!(0..128) {
-        let keys = [[0b1111_1111; INPUT_SIZE_BYTES]]
-            .iter()
-            .map(|v| v.to_owned())
-            .chain(BitsOne::new([0b1111_1111; INPUT_SIZE_BYTES]))
-            .chain(BitsTwo::new([0b1111_1111; INPUT_SIZE_BYTES]));
+fn random(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    for _ in tqdm!(0..config.groups(TEXTS_PER_KEY), position = position) {
+        let cipher = config.cipher(&config.random_key(rng));
+        let blocks = (0..TEXTS_PER_KEY)
+            .map(|_| random_input(rng))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|text| cipher.encrypt(u8_to_dna(&text)))
+            .collect::<Vec<_>>();
+        write_blocks(output, &blocks)?;
+    }
+    Ok(())
+}
 
-        for key in keys {
-            let key = u8_to_dna(key);
-            let cipher = DNAC::new_default(key);
+fn key_density(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+    base: u8,
+) -> io::Result<()> {
+    let keys = density_inputs(vec![base; config.key_size]);
+
+    for _ in tqdm!(0..config.groups(keys.len()), position = position) {
+        let texts = keys.iter().map(|_| random_input(rng)).collect::<Vec<_>>();
+        let blocks = keys
+            .par_iter()
+            .zip(texts.into_par_iter())
+            .map(|(key, text)| config.cipher(key).encrypt(u8_to_dna(&text)))
+            .collect::<Vec<_>>();
+        write_blocks(output, &blocks)?;
+    }
+    Ok(())
+}
 
-            let mut text = [0; INPUT_SIZE_BYTES];
-            inputs.read_exact(&mut text)?;
-            let text = u8_to_dna(text.try_into().unwrap());
-            let block = cipher.encrypt(text.clone());
-            write_block(output.try_clone()?, &block)?;
-        }
+fn plaintext_density(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+    base: u8,
+) -> io::Result<()> {
+    let texts = density_inputs(vec![base; INPUT_SIZE_BYTES]);
+
+    for _ in tqdm!(0..config.groups(texts.len()), position = position) {
+        let cipher = config.cipher(&config.random_key(rng));
+        let blocks = texts
+            .par_iter()
+            .map(|text| cipher.encrypt(u8_to_dna(text)))
+            .collect::<Vec<_>>();
+        write_blocks(output, &blocks)?;
     }
     Ok(())
 }
 
-fn plaintext_high_density(output: File) -> io::Result<()> {
-    let mut inputs = BufReader::new(File::open(RAND_FILE)?);
+fn key_low_density(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    key_density(output, rng, config, position, 0)
+}
 
-    for _ in tqdm!(0..128) {
-        let mut key = [0; INPUT_SIZE_BYTES];
-        inputs.read_exact(&mut key)?;
-        let key = u8_to_dna(key);
-        let cipher = DNAC::new_default(key);
+fn plaintext_low_density(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    plaintext_density(output, rng, config, position, 0)
+}
 
-        let texts = [[0b1111_1111; INPUT_SIZE_BYTES]]
-            .iter()
-            .map(|v| v.to_owned())
-            .chain(BitsOne::new([0b1111_1111; INPUT_SIZE_BYTES]))
-            .chain(BitsTwo::new([0b1111_1111; INPUT_SIZE_BYTES]));
+fn key_high_density(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    key_density(output, rng, config, position, 0b1111_1111)
+}
 
-        for text in texts {
-            let text = u8_to_dna(text);
-            let block = cipher.encrypt(text.clone());
-            write_block(output.try_clone()?, &block)?;
-        }
+fn plaintext_high_density(
+    output: &mut dyn Write,
+    rng: &mut StdRng,
+    config: &Config,
+    position: u16,
+) -> io::Result<()> {
+    plaintext_density(output, rng, config, position, 0b1111_1111)
+}
+
+// sidecar file with everything needed to regenerate the dataset
+fn write_metadata(output: &str, dataset: &Commands, config: &Config, seed: u64) -> io::Result<()> {
+    fs::write(
+        format!("{}.json", output),
+        format!(
+            "{{\"dataset\":\"{}\",\"seed\":{},\"sequences\":{},\"sequence_length\":{},\"key_size\":{},\"rounds\":{},\"format\":\"{}\",\"version\":\"{}\"}}\n",
+            dataset.name(),
+            seed,
+            config.sequences,
+            config.length,
+            config.key_size,
+            config.rounds,
+            config.format.to_possible_value().unwrap().get_name(),
+            env!("CARGO_PKG_VERSION")
+        ),
+    )
+}
+
+// answers to the prompts of the reference `assess` binary: input from a file in
+// `assess_dir`, all tests with default parameters, given number of sequences,
+// ASCII or binary format
+fn write_assess_input(output: &str, assess_dir: &str, config: &Config) -> io::Result<()> {
+    let binary = match config.format {
+        Format::Binary => 1,
+        Format::Ascii => 0,
+        _ => return Ok(()),
+    };
+    let input = Path::new(assess_dir).join(Path::new(output).file_name().unwrap_or_default());
+    fs::write(
+        format!("{}.assess", output),
+        format!(
+            "0\n{}\n1\n0\n{}\n{}\n",
+            input.display(),
+            config.sequences,
+            binary
+        ),
+    )?;
+    eprintln!("./assess {} < {}.assess", config.length, input.display());
+    Ok(())
+}
+
+fn generate(
+    dataset: &Commands,
+    generator: Generator,
+    output_file: &str,
+    config: &Config,
+    seed: u64,
+    position: u16,
+    args: &Args,
+) -> io::Result<()> {
+    let to_stdout = output_file == STDOUT;
+    if args.report && (to_stdout || config.format != Format::Binary) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "reports can only be generated for binary files",
+        ));
+    }
+    let mut output: BufWriter<Box<dyn Write>> = BufWriter::new(if to_stdout {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(output_file)?,
+        )
+    });
+    if config.format == Format::Dieharder {
+        write!(
+            output,
+            "#==================================================================\n\
+             # generator dnac-{}  seed = {}\n\
+             #==================================================================\n\
+             type: d\ncount: {}\nnumbit: 32\n",
+            dataset.name(),
+            seed,
+            config.blocks() * INPUT_SIZE_BYTES / 4
+        )?;
+    }
+    let mut encoder = Encoder {
+        inner: &mut output,
+        format: config.format,
+        pending: Vec::new(),
+    };
+    let mut truncated = Truncated {
+        inner: &mut encoder,
+        remaining: config.blocks() * INPUT_SIZE_BYTES,
+    };
+    generator(
+        &mut truncated,
+        &mut StdRng::seed_from_u64(seed),
+        config,
+        position,
+    )?;
+    output.flush()?;
+    if to_stdout {
+        return Ok(());
+    }
+    write_metadata(output_file, dataset, config, seed)?;
+    write_assess_input(output_file, &args.assess_dir, config)?;
+    if args.report {
+        assess(output_file, config.length)?;
+    }
+    Ok(())
+}
+
+fn assess(input: &str, length: usize) -> io::Result<()> {
+    if !length.is_multiple_of(8) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "sequence length must be a multiple of 8",
+        ));
+    }
+    let data = fs::read(input)?;
+    let parameters = Parameters::default();
+    let outcomes = data
+        .par_chunks_exact(length / 8)
+        .map(|sequence| run_tests(&bits_from_bytes(sequence), &parameters))
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut report = FinalReport::new(input);
+    for outcome in &outcomes {
+        report.add(outcome);
+    }
+    fs::write(
+        format!("{}_finalAnalysisReport.txt", input),
+        report.to_string(),
+    )
+}
+
+fn summary(reports: &[String]) -> io::Result<()> {
+    let mut summary = Summary::new();
+    for report in reports {
+        let parsed = ParsedReport::parse(&fs::read_to_string(report)?).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", report, e))
+        })?;
+        summary.add(&parsed);
+    }
+    print!("{}", summary);
+    if !summary.passed() {
+        return Err(io::Error::other("some tests failed"));
     }
     Ok(())
 }
@@ -269,18 +658,17 @@ fn plaintext_high_density(output: File) -> io::Result<()> {
 fn main() -> io::Result<()> {
     let args = Args::parse();
 
-    let output =
-        OpenOptions::new()
-            .write(true)
-            .create(true)
-            .open(if let Some(file) = args.output {
-                file
-            } else {
-                "resut.blb".to_string()
-            })?;
+    match &args.command {
+        Some(Commands::Assess { input, length }) => return assess(input, *length),
+        Some(Commands::Summary { reports }) => return summary(reports),
+        _ => (),
+    }
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+    eprintln!("seed: {}", seed);
 
     let cmd_map = {
-        let mut map: HashMap<Commands, fn(File) -> io::Result<()>> = HashMap::new();
+        let mut map: HashMap<Commands, Generator> = HashMap::new();
         map.insert(Commands::KeyAv, key_avalanche);
         map.insert(Commands::PlaintextAv, plaintext_avalanche);
         map.insert(Commands::Correlation, correlation);
@@ -293,17 +681,51 @@ fn main() -> io::Result<()> {
         map
     };
 
-    match args.command {
-        Some(cmd) => cmd_map[&cmd](output),
-        None => cmd_map
-            .iter()
-            .map(|(key, function)| {
-                let output = OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .open(key.to_string())?;
-                function(output)
-            })
-            .collect(),
+    match &args.command {
+        Some(_) if args.all => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--all cannot be used with a dataset",
+        )),
+        None if args.output.is_some() => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--output can only be used with a single dataset",
+        )),
+        Some(cmd) => {
+            let output_file = args
+                .output
+                .clone()
+                .unwrap_or(format!("result.{}", args.format.extension()));
+            let config = Config::new(&args, cmd)?;
+            generate(cmd, cmd_map[cmd], &output_file, &config, seed, 0, &args)
+        }
+        None if args.all => cmd_map
+            .into_iter()
+            .enumerate()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .try_for_each(|(position, (key, function))| {
+                let config = Config::new(&args, &key)?;
+                generate(
+                    &key,
+                    function,
+                    &key.file_name(args.format),
+                    &config,
+                    seed,
+                    position as u16,
+                    &args,
+                )
+            }),
+        None => cmd_map.iter().try_for_each(|(key, &function)| {
+            let config = Config::new(&args, key)?;
+            generate(
+                key,
+                function,
+                &key.file_name(args.format),
+                &config,
+                seed,
+                0,
+                &args,
+            )
+        }),
     }
 }
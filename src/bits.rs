@@ -1,25 +1,38 @@
+//! Iterators over copies of a block of bytes with some of its bits changed.
+//! Blocks can be byte arrays of any length or vectors; bits are numbered from
+//! the most significant bit of the first byte.
+
+use rand::{seq::index, Rng};
+
 pub const INPUT_SIZE_BYTES: usize = 16;
-pub struct BitsOne {
-    bits: [u8; INPUT_SIZE_BYTES],
+
+fn bit_len<T: AsRef<[u8]>>(bits: &T) -> usize {
+    bits.as_ref().len() * 8
+}
+
+fn flip<T: AsMut<[u8]>>(bits: &mut T, n_bit: usize) {
+    bits.as_mut()[n_bit / 8] ^= 0b1000_0000 >> (n_bit % 8);
+}
+
+/// Flips every bit of the block in turn.
+pub struct BitsOne<T = [u8; INPUT_SIZE_BYTES]> {
+    bits: T,
     n_bit: usize,
 }
 
-impl BitsOne {
-    pub fn new(bits: [u8; INPUT_SIZE_BYTES]) -> Self {
+impl<T> BitsOne<T> {
+    pub fn new(bits: T) -> Self {
         BitsOne { bits, n_bit: 0 }
     }
 }
 
-impl Iterator for BitsOne {
-    type Item = [u8; INPUT_SIZE_BYTES];
+impl<T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for BitsOne<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.n_bit < INPUT_SIZE_BYTES * 8 {
-            let u8_idx = self.n_bit / 8;
-            let mask = 0b10000000 >> (self.n_bit % 8);
-
-            let mut new_bits = self.bits;
-            new_bits[u8_idx] ^= mask;
+        if self.n_bit < bit_len(&self.bits) {
+            let mut new_bits = self.bits.clone();
+            flip(&mut new_bits, self.n_bit);
 
             self.n_bit += 1;
 
@@ -30,38 +43,63 @@ impl Iterator for BitsOne {
     }
 }
 
-pub struct BitsAll {
-    bits: [u8; INPUT_SIZE_BYTES],
-    bits_iter: Vec<Vec<usize>>,
+/// Replaces every base (pair of bits) with each of its three alternatives in turn.
+pub struct BasesOne<T = [u8; INPUT_SIZE_BYTES]> {
+    bits: T,
+    n_change: usize,
+}
+
+impl<T> BasesOne<T> {
+    pub fn new(bits: T) -> Self {
+        BasesOne { bits, n_change: 0 }
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for BasesOne<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.n_change < bit_len(&self.bits) / 2 * 3 {
+            let base = self.n_change / 3;
+            let difference = (self.n_change % 3 + 1) as u8;
+
+            let mut new_bits = self.bits.clone();
+            new_bits.as_mut()[base / 4] ^= difference << (6 - 2 * (base % 4));
+
+            self.n_change += 1;
+            Some(new_bits)
+        } else {
+            None
+        }
+    }
+}
+
+/// All 2^`n_change` combinations of changes of the first `n_change` bits,
+/// in the order of [`powerset`].
+pub struct BitsAll<T = [u8; INPUT_SIZE_BYTES]> {
+    bits: T,
     n_bit: usize,
     n_change: u32,
 }
 
-impl BitsAll {
-    pub fn new(bits: [u8; INPUT_SIZE_BYTES], n_change: u32) -> Self {
-        let bits_iter = powerset(&Vec::from_iter(0..(n_change as usize)))
-            .map(|v| v.iter().map(|&&a| a).collect::<Vec<usize>>())
-            .collect();
+impl<T> BitsAll<T> {
+    pub fn new(bits: T, n_change: u32) -> Self {
         BitsAll {
             bits,
-            bits_iter,
             n_bit: 0,
             n_change,
         }
     }
 }
 
-impl Iterator for BitsAll {
-    type Item = [u8; INPUT_SIZE_BYTES];
+impl<T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for BitsAll<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.n_bit < 2usize.pow(self.n_change) {
-            let idxs = &self.bits_iter[self.n_bit];
             let mut new_bits = self.bits.clone();
-            for idx in idxs {
-                let i = idx / 8;
-                let j = idx % 8;
-                new_bits[i] ^= 0b1000_0000 >> j;
+            for idx in (0..self.n_change as usize).filter(|i| (self.n_bit >> i) & 1 == 1) {
+                flip(&mut new_bits, idx);
             }
 
             self.n_bit += 1;
@@ -72,48 +110,137 @@ impl Iterator for BitsAll {
     }
 }
 
-pub struct BitsTwo {
-    bits: [u8; INPUT_SIZE_BYTES],
-    fst_bit: usize,
-    snd_bit: usize,
+/// Flips every combination of `k` bits, in lexicographic order of bit positions.
+pub struct BitsK<T = [u8; INPUT_SIZE_BYTES]> {
+    bits: T,
+    positions: Vec<usize>,
+    done: bool,
 }
 
-impl BitsTwo {
-    pub fn new(bits: [u8; INPUT_SIZE_BYTES]) -> Self {
-        BitsTwo {
+impl<T: AsRef<[u8]>> BitsK<T> {
+    pub fn new(bits: T, k: usize) -> Self {
+        let done = k > bit_len(&bits);
+        BitsK {
             bits,
-            fst_bit: 0,
-            snd_bit: 1,
+            positions: (0..k).collect(),
+            done,
         }
     }
 }
 
-impl Iterator for BitsTwo {
-    type Item = [u8; INPUT_SIZE_BYTES];
+impl<T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for BitsK<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // second bit reached the end - move first one up
-        // eg. 01000001 -> 00110000
-        if self.snd_bit >= INPUT_SIZE_BYTES * 8 {
-            self.fst_bit += 1;
-            self.snd_bit = self.fst_bit + 1;
-        }
-        // first bit reached the end - finish sequence
-        if self.fst_bit >= (INPUT_SIZE_BYTES * 8) - 1 {
+        if self.done {
             return None;
         }
-        let fst_u8_idx = self.fst_bit / 8;
-        let fst_mask = 0b10000000 >> (self.fst_bit % 8);
+        let mut new_bits = self.bits.clone();
+        for &position in &self.positions {
+            flip(&mut new_bits, position);
+        }
 
-        let snd_u8_idx = self.snd_bit / 8;
-        let snd_mask = 0b10000000 >> (self.snd_bit % 8);
+        // move up the last position which is not at its end and reset the following ones
+        // eg. 00110001 -> 00110010, 00110011 -> 00111100
+        let n = bit_len(&self.bits);
+        let k = self.positions.len();
+        match (0..k).rev().find(|&i| self.positions[i] < n - k + i) {
+            Some(i) => {
+                self.positions[i] += 1;
+                for j in i + 1..k {
+                    self.positions[j] = self.positions[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(new_bits)
+    }
+}
 
-        let mut new_bits = self.bits;
-        new_bits[fst_u8_idx] ^= fst_mask;
-        new_bits[snd_u8_idx] ^= snd_mask;
+/// Flips every pair of bits.
+pub struct BitsTwo<T = [u8; INPUT_SIZE_BYTES]>(BitsK<T>);
 
-        self.snd_bit += 1;
-        Some(new_bits)
+impl<T: AsRef<[u8]>> BitsTwo<T> {
+    pub fn new(bits: T) -> Self {
+        BitsTwo(BitsK::new(bits, 2))
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for BitsTwo<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// All 2^`n_change` combinations of changes of the first `n_change` bits in Gray code
+/// order, starting with the unchanged block, so consecutive items differ in a single bit.
+pub struct GrayCode<T = [u8; INPUT_SIZE_BYTES]> {
+    bits: T,
+    step: usize,
+    n_change: u32,
+}
+
+impl<T> GrayCode<T> {
+    pub fn new(bits: T, n_change: u32) -> Self {
+        GrayCode {
+            bits,
+            step: 0,
+            n_change,
+        }
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for GrayCode<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step < 2usize.pow(self.n_change) {
+            if self.step > 0 {
+                flip(&mut self.bits, self.step.trailing_zeros() as usize);
+            }
+            self.step += 1;
+            Some(self.bits.clone())
+        } else {
+            None
+        }
+    }
+}
+
+/// `samples` copies of the block, each with `k` random distinct bits flipped.
+pub struct BitsRandom<R, T = [u8; INPUT_SIZE_BYTES]> {
+    bits: T,
+    k: usize,
+    samples: usize,
+    rng: R,
+}
+
+impl<R, T> BitsRandom<R, T> {
+    pub fn new(bits: T, k: usize, samples: usize, rng: R) -> Self {
+        BitsRandom {
+            bits,
+            k,
+            samples,
+            rng,
+        }
+    }
+}
+
+impl<R: Rng, T: AsRef<[u8]> + AsMut<[u8]> + Clone> Iterator for BitsRandom<R, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.samples > 0 {
+            let mut new_bits = self.bits.clone();
+            for position in index::sample(&mut self.rng, bit_len(&self.bits), self.k) {
+                flip(&mut new_bits, position);
+            }
+            self.samples -= 1;
+            Some(new_bits)
+        } else {
+            None
+        }
     }
 }
 
@@ -126,3 +253,50 @@ pub fn powerset<T>(s: &[T]) -> impl Iterator<Item = Vec<&T>> {
             .collect()
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn distance(a: &[u8], b: &[u8]) -> u32 {
+        a.iter().zip(b).map(|(a, b)| (a ^ b).count_ones()).sum()
+    }
+
+    #[test]
+    fn test_block_sizes() {
+        let key = [0u8; 32];
+        assert_eq!(BitsOne::new(key).count(), 256);
+        assert_eq!(BitsTwo::new(vec![0u8; 32]).count(), 256 * 255 / 2);
+        assert_eq!(BitsK::new([0u8; 2], 3).count(), 560);
+        assert_eq!(BasesOne::new([0u8; 2]).count(), 24);
+        assert_eq!(BitsOne::new([0u8; 2]).last(), Some([0, 1]));
+        assert_eq!(
+            BitsTwo::new([0u8; 1]).take(2).collect::<Vec<_>>(),
+            [[0b1100_0000], [0b1010_0000]]
+        );
+    }
+
+    #[test]
+    fn test_all_and_gray_code() {
+        let all = BitsAll::new([0u8; 2], 10).collect::<Vec<_>>();
+        let mut gray = GrayCode::new([0u8; 2], 10).collect::<Vec<_>>();
+        assert!(gray.windows(2).all(|w| distance(&w[0], &w[1]) == 1));
+        gray.sort();
+        let mut sorted = all.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(gray, sorted);
+        assert_eq!(all[3], [0b1100_0000, 0]);
+    }
+
+    #[test]
+    fn test_random_sample() {
+        let samples = BitsRandom::new(vec![0xffu8; 32], 5, 100, rand::thread_rng());
+        assert_eq!(
+            samples
+                .map(|s| distance(&s, &[0xff; 32]))
+                .collect::<Vec<_>>(),
+            vec![5; 100]
+        );
+    }
+}
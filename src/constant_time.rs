@@ -0,0 +1,196 @@
+//! Round function without branches or loop counts depending on the key or the data:
+//! the intron search visits every source position and selects with masks, the S-box
+//! table is read whole and XOR tables are packed into integers indexed by shifts.
+//! The only memory accesses at data dependent addresses read the 4-byte table
+//! converting 2-bit values back to bases, which fits in a single cache line.
+
+use crate::{
+    dna::{
+        xors::{get_xor_index, XORS},
+        DNA,
+    },
+    sbox::SBox,
+    INPUT_SIZE, INTRON_SIZE, KEY_SIZE, SOURCE_SIZE, TARGET_SIZE,
+};
+
+const BASES: [DNA; 4] = [DNA::A, DNA::C, DNA::G, DNA::T];
+const PATTERNS: usize = (KEY_SIZE - TARGET_SIZE - 2) / 2;
+// introns found before the search stops, `intron_idx < 10` in the reference round
+const MAX_INTRONS: u64 = 5;
+
+// masks are all ones for true and zero for false, values must be below 2^63;
+// black_box keeps the compiler from turning masked selections back into branches
+fn lt(a: u64, b: u64) -> u64 {
+    std::hint::black_box(0u64.wrapping_sub(a.wrapping_sub(b) >> 63))
+}
+
+fn eq(a: u64, b: u64) -> u64 {
+    lt(a ^ b, 1)
+}
+
+fn select(mask: u64, a: u64, b: u64) -> u64 {
+    (a & mask) | (b & !mask)
+}
+
+fn min(a: u64, b: u64) -> u64 {
+    select(lt(a, b), a, b)
+}
+
+// two bases as a 4-bit value
+fn pair(bases: &[u64]) -> u64 {
+    (bases[0] << 2) | bases[1]
+}
+
+// a match could compile to branches, the index into the table of 4 bytes depends on the
+// data but the whole table is in a single cache line
+fn to_base(x: u64) -> DNA {
+    BASES[(x & 0b11) as usize]
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ConstantTimeRound {
+    // S-box outputs of 4 bases packed into bytes, 8 per word
+    sbox: [u64; 32],
+    // results of every XOR for the 16 pairs of bases, 2 bits each
+    xors: [u64; 6],
+    // XOR selected by each of the 16 pairs of key bases, 3 bits each
+    selector: u64,
+}
+
+impl ConstantTimeRound {
+    pub(crate) fn new(sbox: &SBox) -> Self {
+        let mut table = [0; 32];
+        for i in 0..256 {
+            let input = [0, 1, 2, 3].map(|j| BASES[(i >> (6 - 2 * j)) & 0b11]);
+            let output = sbox[&input].iter().fold(0, |acc, &b| (acc << 2) | b as u64);
+            table[i / 8] |= output << (8 * (i % 8));
+        }
+
+        let mut xors = [0; 6];
+        let mut selector = 0;
+        for (a, b) in (0..4).flat_map(|a| (0..4).map(move |b| (a, b))) {
+            let index = 4 * a + b;
+            for (table, xor) in xors.iter_mut().zip(XORS) {
+                *table |= (xor(BASES[a], BASES[b]) as u64) << (2 * index);
+            }
+            selector |= (get_xor_index(&[BASES[a], BASES[b]]) as u64) << (3 * index);
+        }
+        ConstantTimeRound {
+            sbox: table,
+            xors,
+            selector,
+        }
+    }
+
+    fn sbox(&self, input: u64) -> u64 {
+        let word = self
+            .sbox
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &w)| acc | (w & eq(i as u64, input >> 3)));
+        (word >> (8 * (input & 0b111))) & 0xff
+    }
+
+    /// Same as the reference round of [`DNAC`](crate::DNAC).
+    pub(crate) fn round(
+        &self,
+        input: &[DNA; INPUT_SIZE],
+        key: &[DNA; KEY_SIZE],
+    ) -> [DNA; INPUT_SIZE] {
+        let state = input.map(|b| b as u64);
+        let key = key.map(|b| b as u64);
+        let (source, target) = state.split_at(SOURCE_SIZE);
+        let patterns: [u64; PATTERNS] =
+            std::array::from_fn(|i| pair(&key[TARGET_SIZE + 2 + 2 * i..]));
+
+        // source position of every base of the intron, the bases are gathered after the search
+        let mut positions = [0; TARGET_SIZE];
+        let mut intron_len = 0;
+        let mut introns = 0;
+        // source positions left to skip after the start of the last intron
+        let mut skip = 0;
+        for p in 0..SOURCE_SIZE - 1 {
+            let source_pattern = pair(&source[p..]);
+            let found = patterns
+                .iter()
+                .fold(0, |acc, &k| acc | eq(source_pattern, k));
+            let start = found & eq(skip, 0) & lt(introns, MAX_INTRONS);
+            let len = min(
+                min(
+                    (SOURCE_SIZE - 1 - p) as u64,
+                    TARGET_SIZE as u64 - intron_len,
+                ),
+                INTRON_SIZE as u64,
+            );
+            for (t, position) in positions.iter_mut().enumerate() {
+                let t = t as u64;
+                let copy = start & !lt(t, intron_len) & lt(t, intron_len + len);
+                *position = select(copy, (p as u64 + t).wrapping_sub(intron_len), *position);
+            }
+            intron_len += len & start;
+            introns += 1 & start;
+            skip = select(
+                start,
+                len.wrapping_sub(1 & !eq(len, 0)),
+                skip.wrapping_sub(1 & !eq(skip, 0)),
+            );
+        }
+        let intron: [u64; TARGET_SIZE] = std::array::from_fn(|t| {
+            let base = source
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, &b)| acc | (b & eq(i as u64, positions[t])));
+            // the rest of the intron is filled with A's
+            base & lt(t as u64, intron_len)
+        });
+
+        let xor_index = (self.selector >> (3 * pair(&key[TARGET_SIZE..]))) & 0b111;
+        let xor = self
+            .xors
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &t)| acc | (t & eq(i as u64, xor_index)));
+        let dna_xor = |a: u64, b: u64| (xor >> (2 * ((a << 2) | b))) & 0b11;
+
+        let mut result = *input;
+        for (i, chunk) in intron.chunks_exact(4).enumerate() {
+            let output = self.sbox(chunk.iter().fold(0, |acc, &b| (acc << 2) | b));
+            for j in 0..4 {
+                let n = 4 * i + j;
+                let intron_base = (output >> (6 - 2 * j)) & 0b11;
+                // order is important - target must be the first argument
+                result[SOURCE_SIZE + n] = to_base(dna_xor(target[n], dna_xor(key[n], intron_base)));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{analysis::random_dna, DNAC};
+
+    #[test]
+    fn test_matches_reference() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0..64 {
+            let key = random_dna(rng, 32);
+            let reference = DNAC::new(key.clone(), 22);
+            let constant_time = DNAC::new(key, 22).constant_time();
+            let mut texts = vec![random_dna(rng, 64)];
+            // inputs made of a single pattern contain as many introns as possible
+            for base in BASES {
+                texts.push(vec![base; 4 * INPUT_SIZE]);
+            }
+            for text in texts {
+                let encrypted = constant_time.encrypt(text.clone());
+                assert_eq!(encrypted, reference.encrypt(text));
+                assert_eq!(
+                    constant_time.decrypt(encrypted.clone()),
+                    reference.decrypt(encrypted)
+                );
+            }
+        }
+    }
+}
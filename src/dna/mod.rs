@@ -1,11 +1,11 @@
 use std::{
-    fmt::Debug,
+    fmt::{Debug, Display},
     ops::{Add, BitXor, Sub},
 };
 
 pub mod xors;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DNA {
     A,
     C,
@@ -25,6 +25,18 @@ impl Debug for DNA {
     }
 }
 
+impl Display for DNA {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DNA::A => "A",
+            DNA::C => "C",
+            DNA::G => "G",
+            DNA::T => "T",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 impl BitXor for DNA {
     type Output = Self;
 
@@ -101,8 +113,8 @@ impl BitXor for DNAWord {
 
     fn bitxor(self, rhs: Self) -> Self::Output {
         let mut result = [DNA::A; 4];
-        for i in 0..4 {
-            result[i] = self.0[i] ^ rhs.0[i];
+        for (i, base) in result.iter_mut().enumerate() {
+            *base = self.0[i] ^ rhs.0[i];
         }
         DNAWord(result)
     }
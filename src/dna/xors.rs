@@ -172,18 +172,26 @@ pub fn dnaxor_6(a: DNA, b: DNA) -> DNA {
     }
 }
 
-pub fn get_xor(key: &[DNA]) -> fn(DNA, DNA) -> DNA {
-    trace!("get xor key = {:?}", key);
+pub const XORS: [fn(DNA, DNA) -> DNA; 6] =
+    [dnaxor_1, dnaxor_2, dnaxor_3, dnaxor_4, dnaxor_5, dnaxor_6];
+
+// returns index (0-based) of the xor definition in XORS selected by key
+pub fn get_xor_index(key: &[DNA]) -> usize {
     match key {
-        [DNA::A, DNA::A] | [DNA::C, DNA::C] | [DNA::G, DNA::G] | [DNA::T, DNA::T] => dnaxor_1,
-        [DNA::A, DNA::C] | [DNA::A, DNA::G] | [DNA::A, DNA::T] => dnaxor_2,
-        [DNA::C, DNA::A] | [DNA::C, DNA::G] | [DNA::C, DNA::T] => dnaxor_3,
-        [DNA::G, DNA::A] | [DNA::G, DNA::C] | [DNA::G, DNA::T] => dnaxor_4,
-        [DNA::T, DNA::C] | [DNA::T, DNA::G] | [DNA::T, DNA::A] => dnaxor_5,
-        _ => dnaxor_6, // should never match
+        [DNA::A, DNA::A] | [DNA::C, DNA::C] | [DNA::G, DNA::G] | [DNA::T, DNA::T] => 0,
+        [DNA::A, DNA::C] | [DNA::A, DNA::G] | [DNA::A, DNA::T] => 1,
+        [DNA::C, DNA::A] | [DNA::C, DNA::G] | [DNA::C, DNA::T] => 2,
+        [DNA::G, DNA::A] | [DNA::G, DNA::C] | [DNA::G, DNA::T] => 3,
+        [DNA::T, DNA::C] | [DNA::T, DNA::G] | [DNA::T, DNA::A] => 4,
+        _ => 5, // should never match
     }
 }
 
+pub fn get_xor(key: &[DNA]) -> fn(DNA, DNA) -> DNA {
+    trace!("get xor key = {:?}", key);
+    XORS[get_xor_index(key)]
+}
+
 pub fn word_xor(a: [DNA; 4], b: [DNA; 4]) -> [DNA; 4] {
     let mut result = [DNA::A; 4];
     for i in 0..4 {
@@ -0,0 +1,253 @@
+//! Known answer tests: ciphertexts of fixed keys and plaintexts for several round
+//! counts and modes, stored in `tests/data/kat.rsp` in the format of the NIST response files.
+
+use std::fmt::Write;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    analysis::parse_hex,
+    dna::{binary_to_DNA, DNA_to_binary, DNA},
+    modes::{self, Mode},
+    DNAC,
+};
+
+const ECB_ROUNDS: [usize; 8] = [1, 2, 3, 4, 8, 16, 22, 32];
+const KEY_SIZES: [usize; 2] = [16, 32];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vector {
+    pub count: usize,
+    pub rounds: usize,
+    pub key: Vec<u8>,
+    /// IV of CBC or nonce of CTR, empty for ECB
+    pub iv: Vec<u8>,
+    pub plaintext: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+pub(crate) fn to_dna(bytes: &[u8]) -> Vec<DNA> {
+    bytes.iter().flat_map(binary_to_DNA).collect()
+}
+
+pub(crate) fn to_bytes(dna: &[DNA]) -> Vec<u8> {
+    dna.chunks_exact(4)
+        .map(|chunk| DNA_to_binary(chunk.try_into().unwrap()))
+        .collect()
+}
+
+impl Vector {
+    fn cipher(&self) -> Result<DNAC, String> {
+        DNAC::try_new(to_dna(&self.key), self.rounds)
+    }
+
+    fn encrypt(&self, mode: Mode) -> Result<Vec<u8>, String> {
+        let ciphertext = modes::encrypt(
+            &self.cipher()?,
+            mode,
+            &to_dna(&self.iv),
+            to_dna(&self.plaintext),
+        )?;
+        Ok(to_bytes(&ciphertext))
+    }
+
+    /// Checks encryption and decryption of the vector in the given mode.
+    pub fn check(&self, mode: Mode) -> Result<(), String> {
+        let ciphertext = self.encrypt(mode)?;
+        if ciphertext != self.ciphertext {
+            return Err(format!(
+                "{} {}: expected ciphertext {}, got {}",
+                mode.name(),
+                self.count,
+                hex(&self.ciphertext),
+                hex(&ciphertext)
+            ));
+        }
+        let plaintext = modes::decrypt(
+            &self.cipher()?,
+            mode,
+            &to_dna(&self.iv),
+            to_dna(&self.ciphertext),
+        )?;
+        if to_bytes(&plaintext) != self.plaintext {
+            return Err(format!(
+                "{} {}: decryption does not give the plaintext",
+                mode.name(),
+                self.count
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parses vectors grouped by `[ECB]`, `[CBC]` and `[CTR]` sections.
+pub fn parse(text: &str) -> Result<Vec<(Mode, Vector)>, String> {
+    parse_sections(text)?
+        .into_iter()
+        .map(|(section, vector)| Ok((Mode::by_name(&section)?, vector)))
+        .collect()
+}
+
+/// Parses vectors in the response file format, each with the name of its section.
+pub(crate) fn parse_sections(text: &str) -> Result<Vec<(String, Vector)>, String> {
+    let mut section = None;
+    let mut vectors: Vec<(String, Vector)> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name.to_string());
+            continue;
+        }
+        let (field, value) = line
+            .split_once('=')
+            .map(|(f, v)| (f.trim(), v.trim()))
+            .ok_or(format!("line {}: expected FIELD = VALUE", n + 1))?;
+        if field == "COUNT" {
+            let section = section
+                .clone()
+                .ok_or(format!("line {}: vector outside of a section", n + 1))?;
+            let count = value
+                .parse()
+                .map_err(|e| format!("line {}: {}", n + 1, e))?;
+            vectors.push((
+                section,
+                Vector {
+                    count,
+                    ..Default::default()
+                },
+            ));
+            continue;
+        }
+        let (_, vector) = vectors
+            .last_mut()
+            .ok_or(format!("line {}: field before COUNT", n + 1))?;
+        match field {
+            "ROUNDS" => {
+                vector.rounds = value
+                    .parse()
+                    .map_err(|e| format!("line {}: {}", n + 1, e))?
+            }
+            "KEY" => vector.key = parse_hex(value)?,
+            "IV" => vector.iv = parse_hex(value)?,
+            "PLAINTEXT" => vector.plaintext = parse_hex(value)?,
+            "CIPHERTEXT" => vector.ciphertext = parse_hex(value)?,
+            _ => return Err(format!("line {}: unknown field {}", n + 1, field)),
+        }
+    }
+    Ok(vectors)
+}
+
+pub fn render(vectors: &[(Mode, Vector)]) -> Result<String, std::fmt::Error> {
+    let vectors = vectors
+        .iter()
+        .map(|(mode, vector)| (mode.name().to_string(), vector.clone()))
+        .collect::<Vec<_>>();
+    render_sections(
+        "DNAC known answer tests, generated with `dnac kat`",
+        &vectors,
+    )
+}
+
+pub(crate) fn render_sections(
+    title: &str,
+    vectors: &[(String, Vector)],
+) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, "# {}", title)?;
+    writeln!(
+        out,
+        "# all values are hex bytes, bases are encoded as A = 00, G = 01, C = 10, T = 11"
+    )?;
+    let mut current = None;
+    for (section, vector) in vectors {
+        if current != Some(section) {
+            writeln!(out)?;
+            writeln!(out, "[{}]", section)?;
+            current = Some(section);
+        }
+        writeln!(out)?;
+        writeln!(out, "COUNT = {}", vector.count)?;
+        writeln!(out, "ROUNDS = {}", vector.rounds)?;
+        writeln!(out, "KEY = {}", hex(&vector.key))?;
+        if !vector.iv.is_empty() {
+            writeln!(out, "IV = {}", hex(&vector.iv))?;
+        }
+        writeln!(out, "PLAINTEXT = {}", hex(&vector.plaintext))?;
+        writeln!(out, "CIPHERTEXT = {}", hex(&vector.ciphertext))?;
+    }
+    Ok(out)
+}
+
+/// Vectors computed with the current implementation: all-zero and random keys and
+/// plaintexts for several round counts in ECB, and multi-block messages in CBC and CTR.
+pub fn generate() -> Result<Vec<(Mode, Vector)>, String> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut random = |size: usize| {
+        let mut bytes = vec![0; size];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    };
+    let mut vectors = Vec::new();
+    for key_size in KEY_SIZES {
+        vectors.push((Mode::Ecb, vec![0; key_size], vec![], vec![0; 16], 22));
+        for rounds in ECB_ROUNDS {
+            vectors.push((Mode::Ecb, random(key_size), vec![], random(16), rounds));
+        }
+    }
+    for mode in [Mode::Cbc, Mode::Ctr] {
+        for key_size in KEY_SIZES {
+            // CTR messages do not have to be a multiple of the block size
+            let length = if mode == Mode::Ctr { 40 } else { 48 };
+            vectors.push((mode, random(key_size), random(16), random(length), 22));
+        }
+    }
+
+    let mut counts = [0; 3];
+    vectors
+        .into_iter()
+        .map(|(mode, key, iv, plaintext, rounds)| {
+            let count = &mut counts[mode as usize];
+            let mut vector = Vector {
+                count: *count,
+                rounds,
+                key,
+                iv,
+                plaintext,
+                ciphertext: Vec::new(),
+            };
+            *count += 1;
+            vector.ciphertext = vector.encrypt(mode)?;
+            Ok((mode, vector))
+        })
+        .collect()
+}
+
+/// Checks all vectors of `text`, returns their number.
+pub fn selftest(text: &str) -> Result<usize, String> {
+    let vectors = parse(text)?;
+    for (mode, vector) in &vectors {
+        vector.check(*mode)?;
+    }
+    Ok(vectors.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_parse() {
+        let vectors = generate().unwrap();
+        assert_eq!(parse(&render(&vectors).unwrap()).unwrap(), vectors);
+        assert!(parse("COUNT = 0").is_err());
+        assert!(parse("[XTS]\nCOUNT = 0").is_err());
+        assert!(parse("[ECB]\nCOUNT = 0\nKEY = aéb0").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    dna::{binary_to_DNA, xors::dnaxor_1, DNA},
+    sbox::SBox,
+    DNAC, INPUT_SIZE, KEY_SIZE,
+};
+
+// fractional part of pi, used as the key of the public permutation of the sponge
+const SPONGE_KEY: [u8; 32] = [
+    0x24, 0x3f, 0x6a, 0x88, 0x85, 0xa3, 0x08, 0xd3, 0x13, 0x19, 0x8a, 0x2e, 0x03, 0x70, 0x73, 0x44,
+    0xa4, 0x09, 0x38, 0x22, 0x29, 0x9f, 0x31, 0xd0, 0x08, 0x2e, 0xfa, 0x98, 0xec, 0x4e, 0x6c, 0x89,
+];
+const SPONGE_RATE: usize = 32;
+
+/// Expands the key into one round key for each round.
+pub trait KeySchedule {
+    fn name(&self) -> &'static str;
+    fn expand(&self, key: &[DNA], rounds: usize) -> Vec<[DNA; KEY_SIZE]>;
+
+    /// Returns an error if keys of `len` bases cannot be expanded.
+    fn check_key_size(&self, _len: usize) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Default, AES-derived schedule.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AesSchedule;
+
+impl KeySchedule for AesSchedule {
+    fn name(&self) -> &'static str {
+        "aes"
+    }
+
+    fn expand(&self, key: &[DNA], rounds: usize) -> Vec<[DNA; KEY_SIZE]> {
+        DNAC::expand_key(key.to_vec(), SBox::new(), rounds).to_vec()
+    }
+
+    fn check_key_size(&self, len: usize) -> Result<(), String> {
+        DNAC::check_key_size(len)
+    }
+}
+
+/// Round keys taken from encryptions of consecutive counter values with
+/// DNAC keyed with the original key (using the default schedule).
+#[derive(Clone, Copy, Debug)]
+pub struct CtrSchedule {
+    pub rounds: usize,
+}
+
+impl Default for CtrSchedule {
+    fn default() -> Self {
+        CtrSchedule { rounds: 22 }
+    }
+}
+
+impl KeySchedule for CtrSchedule {
+    fn name(&self) -> &'static str {
+        "ctr"
+    }
+
+    fn expand(&self, key: &[DNA], rounds: usize) -> Vec<[DNA; KEY_SIZE]> {
+        let cipher = DNAC::new(key.to_vec(), self.rounds);
+        let blocks = (rounds * KEY_SIZE).div_ceil(INPUT_SIZE);
+        let counters = (0..blocks as u128)
+            .flat_map(|counter| counter.to_be_bytes())
+            .flat_map(|b| binary_to_DNA(&b))
+            .collect::<Vec<DNA>>();
+        to_round_keys(&cipher.encrypt(counters), rounds)
+    }
+
+    fn check_key_size(&self, len: usize) -> Result<(), String> {
+        DNAC::check_key_size(len)
+    }
+}
+
+/// Sponge with DNAC keyed with a public constant as the permutation: the key
+/// is absorbed in 32-base blocks and round keys are squeezed out.
+#[derive(Clone, Copy, Debug)]
+pub struct SpongeSchedule {
+    pub rounds: usize,
+}
+
+impl Default for SpongeSchedule {
+    fn default() -> Self {
+        SpongeSchedule { rounds: 22 }
+    }
+}
+
+impl KeySchedule for SpongeSchedule {
+    fn name(&self) -> &'static str {
+        "sponge"
+    }
+
+    fn expand(&self, key: &[DNA], rounds: usize) -> Vec<[DNA; KEY_SIZE]> {
+        let permutation = DNAC::new(
+            SPONGE_KEY.iter().flat_map(binary_to_DNA).collect(),
+            self.rounds,
+        );
+        let permute = |state: Vec<DNA>| permutation.encrypt(state);
+
+        // pad with a single C followed by A's up to a multiple of the rate
+        let mut input = key.to_vec();
+        input.push(DNA::C);
+        input.resize(input.len().div_ceil(SPONGE_RATE) * SPONGE_RATE, DNA::A);
+
+        let mut state = vec![DNA::A; INPUT_SIZE];
+        for chunk in input.chunks_exact(SPONGE_RATE) {
+            for (s, &k) in state.iter_mut().zip(chunk) {
+                *s = dnaxor_1(*s, k);
+            }
+            state = permute(state);
+        }
+
+        let mut output = Vec::with_capacity(rounds * KEY_SIZE + SPONGE_RATE);
+        while output.len() < rounds * KEY_SIZE {
+            output.extend_from_slice(&state[..SPONGE_RATE]);
+            state = permute(state);
+        }
+        to_round_keys(&output, rounds)
+    }
+}
+
+fn to_round_keys(bases: &[DNA], rounds: usize) -> Vec<[DNA; KEY_SIZE]> {
+    bases
+        .chunks_exact(KEY_SIZE)
+        .map(|chunk| chunk.try_into().unwrap())
+        .take(rounds)
+        .collect()
+}
+
+/// Schedule selected by name: "aes", "ctr" or "sponge".
+pub fn schedule_by_name(name: &str) -> Result<Box<dyn KeySchedule + Send + Sync>, String> {
+    match name {
+        "aes" => Ok(Box::new(AesSchedule)),
+        "ctr" => Ok(Box::<CtrSchedule>::default()),
+        "sponge" => Ok(Box::<SpongeSchedule>::default()),
+        _ => Err(format!("unknown key schedule {}", name)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_schedules() {
+        let key = rand::thread_rng()
+            .gen::<[u8; 16]>()
+            .iter()
+            .flat_map(binary_to_DNA)
+            .collect::<Vec<DNA>>();
+        let msg = rand::thread_rng()
+            .gen::<[u8; 16]>()
+            .iter()
+            .flat_map(binary_to_DNA)
+            .collect::<Vec<DNA>>();
+        for name in ["aes", "ctr", "sponge"] {
+            let schedule = schedule_by_name(name).unwrap();
+            assert_eq!(schedule.expand(&key, 22).len(), 22);
+            assert!(schedule.check_key_size(key.len()).is_ok());
+            assert_eq!(schedule.check_key_size(60).is_ok(), name == "sponge");
+            let cipher = DNAC::with_schedule(key.clone(), 22, schedule.as_ref()).unwrap();
+            assert_eq!(
+                DNAC::with_schedule(key[..60].to_vec(), 22, schedule.as_ref()).is_ok(),
+                name == "sponge"
+            );
+            assert_eq!(msg, cipher.decrypt(cipher.encrypt(msg.clone())).unwrap());
+        }
+        assert_eq!(
+            AesSchedule.expand(&key, 22),
+            DNAC::expand_key(key, SBox::new(), 22).to_vec()
+        );
+    }
+}
@@ -2,13 +2,25 @@ use std::sync::Arc;
 
 use log::trace;
 
+pub mod analysis;
 pub mod bits;
+mod constant_time;
+use constant_time::ConstantTimeRound;
 pub mod dna;
 use dna::{
     binary_to_DNA,
-    xors::{get_xor, word_xor},
+    xors::{get_xor_index, word_xor, XORS},
     DNA,
 };
+pub mod key_schedule;
+use key_schedule::KeySchedule;
+pub mod kat;
+pub mod mct;
+pub mod modes;
+pub mod trace;
+use trace::{RoundObserver, RoundTrace};
+pub mod weak_keys;
+use weak_keys::{check_key, KeyPolicy};
 
 mod sbox;
 use sbox::SBox;
@@ -18,27 +30,82 @@ macro_rules! min {
     ($x: expr, $($z: expr),+) => (::std::cmp::min($x, min!($($z),*)));
 }
 
+/// Number of rounds based on test results.
+pub const DEFAULT_ROUNDS: usize = 22;
+
 const TARGET_SIZE: usize = 20;
 const SOURCE_SIZE: usize = 44;
 const INPUT_SIZE: usize = 64;
 const KEY_SIZE: usize = 28;
 const INTRON_SIZE: usize = 8;
+// the default key schedule works on 4-byte words and needs at least 4 of them
+const KEY_WORD_SIZE: usize = 16;
+const MIN_KEY_SIZE: usize = 64;
 // intron size of 6 with target size of 18 uses 3 out of 4 pairs in key
 
 pub struct DNAC {
     sbox: SBox,
     key: Arc<[[DNA; KEY_SIZE]]>,
+    constant_time: Option<ConstantTimeRound>,
 }
 
 impl DNAC {
     pub fn new_default(key: Vec<DNA>) -> DNAC {
-        DNAC::new(key, 22) // nuber of rounds based on test results
+        DNAC::new(key, DEFAULT_ROUNDS)
     }
 
+    /// Panics if the key size is not supported by the default schedule, see [`DNAC::try_new`].
     pub fn new(key: Vec<DNA>, rounds: usize) -> DNAC {
         let sbox = SBox::new();
         let key = DNAC::expand_key(key, sbox, rounds);
-        DNAC { sbox, key }
+        DNAC {
+            sbox,
+            key,
+            constant_time: None,
+        }
+    }
+
+    /// Creates cipher like [`DNAC::new`], returning an error if the key is not a whole
+    /// number of 4-byte words or is shorter than 16 bytes.
+    pub fn try_new(key: Vec<DNA>, rounds: usize) -> Result<DNAC, String> {
+        DNAC::check_key_size(key.len())?;
+        Ok(DNAC::new(key, rounds))
+    }
+
+    // key sizes in bases for which the default schedule works, see [`DNAC::expand_key`]
+    pub(crate) fn check_key_size(len: usize) -> Result<(), String> {
+        if len < MIN_KEY_SIZE || !len.is_multiple_of(KEY_WORD_SIZE) {
+            return Err(format!(
+                "illegal key, length should be a multiple of {} bases and at least {}",
+                KEY_WORD_SIZE, MIN_KEY_SIZE
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates cipher with round keys expanded by `schedule` instead of the default schedule,
+    /// returning an error for key sizes the schedule does not support.
+    pub fn with_schedule(
+        key: Vec<DNA>,
+        rounds: usize,
+        schedule: &dyn KeySchedule,
+    ) -> Result<DNAC, String> {
+        schedule.check_key_size(key.len())?;
+        Ok(DNAC::from_round_keys(schedule.expand(&key, rounds)))
+    }
+
+    pub fn new_with_policy(
+        key: Vec<DNA>,
+        rounds: usize,
+        policy: KeyPolicy,
+    ) -> Result<DNAC, String> {
+        if policy == KeyPolicy::RejectWeak {
+            let report = check_key(&key, rounds)?;
+            if report.is_weak() {
+                return Err(format!("weak key rejected: {:?}", report));
+            }
+        }
+        DNAC::try_new(key, rounds)
     }
 
     fn expand_key(key: Vec<DNA>, sbox: SBox, rounds: usize) -> Arc<[[DNA; KEY_SIZE]]> {
@@ -109,78 +176,110 @@ impl DNAC {
             .collect()
     }
 
-    fn round(&self, input: &[DNA; INPUT_SIZE], key: &[DNA; KEY_SIZE]) -> [DNA; INPUT_SIZE] {
-        let mut result = [DNA::A; INPUT_SIZE];
-        result.copy_from_slice(input.as_slice());
-        let (source, target) = result.split_at_mut(SOURCE_SIZE);
-        let base_key = &key[..TARGET_SIZE];
-        let xor_selector = &key[TARGET_SIZE..TARGET_SIZE + 2];
-        let intron_patterns = &key[TARGET_SIZE + 2..];
-        let intron_patterns = intron_patterns
-            .chunks_exact(2)
-            .map(|c| c.try_into().unwrap())
-            .collect::<Vec<[DNA; 2]>>();
-
-        let mut intron = [DNA::A; TARGET_SIZE];
-        let mut intron_len = 0;
-
-        let mut intron_idx = 0;
-        let mut source_idx = 0;
-        while source_idx < SOURCE_SIZE - 1 && intron_idx < 10 {
-            let source_pattern = source[source_idx..source_idx + 2].try_into().unwrap();
-            if intron_patterns.contains(&source_pattern) {
-                // if intron_patterns[intron_idx..intron_idx + 2] == source[source_idx..source_idx + 2] {
-                let cp_len = min!(
-                    SOURCE_SIZE - 1 - source_idx, // limit to the end of the source block
-                    TARGET_SIZE - intron_len,     // limit to the size of target block
-                    INTRON_SIZE
-                );
-                intron[intron_len..intron_len + cp_len]
-                    .copy_from_slice(&source[source_idx..source_idx + cp_len]);
-                intron_len += cp_len;
-                source_idx += cp_len;
-                intron_idx += 2;
-            } else {
-                source_idx += 1;
-            }
+    // cipher with already expanded round keys, used by the analysis tools
+    pub(crate) fn from_round_keys(key: Vec<[DNA; KEY_SIZE]>) -> DNAC {
+        DNAC {
+            sbox: SBox::new(),
+            key: key.into(),
+            constant_time: None,
         }
-        trace!("intron_len = {}", intron_len);
+    }
 
-        // use last two bases of key to select the xor definition
-        let dna_xor = get_xor(xor_selector);
+    /// Switches to the constant-time round function, which gives the same results without
+    /// branches depending on the key or the data, its only data dependent memory accesses
+    /// stay within a single cache line. Only the rounds are affected: the round keys were
+    /// already expanded with the S-box lookups branching on the key, and
+    /// [`DNAC::encrypt_observed`] still uses the reference implementation.
+    pub fn constant_time(mut self) -> DNAC {
+        self.constant_time = Some(ConstantTimeRound::new(&self.sbox));
+        self
+    }
 
-        intron
-            // transform introns with sbox
-            .chunks_exact(4)
-            .flat_map(|chunk| self.sbox[chunk.try_into().unwrap()].into_iter())
-            .enumerate()
-            .map(|(i, intron_base)| (i, dna_xor(base_key[i], intron_base)))
-            // order is important - target must be the first argument
-            .for_each(|(i, intron_base)| target[i] = dna_xor(target[i], intron_base));
+    fn round(&self, input: &[DNA; INPUT_SIZE], key: &[DNA; KEY_SIZE]) -> [DNA; INPUT_SIZE] {
+        match &self.constant_time {
+            Some(round) => round.round(input, key),
+            None => self.round_traced(input, key, None),
+        }
+    }
+
+    // round of encryption, followed by the swap of blocks in all but the last round
+    pub(crate) fn feistel_round(
+        &self,
+        input: &[DNA; INPUT_SIZE],
+        key: &[DNA; KEY_SIZE],
+        last: bool,
+    ) -> [DNA; INPUT_SIZE] {
+        let result = self.round(input, key);
+        if last {
+            result
+        } else {
+            swap(&result)
+        }
+    }
 
+    fn round_traced(
+        &self,
+        input: &[DNA; INPUT_SIZE],
+        key: &[DNA; KEY_SIZE],
+        trace: Option<&mut RoundTrace>,
+    ) -> [DNA; INPUT_SIZE] {
         // return result table with both source and target blocks
+        let mut result = *input;
+        round_in_place(
+            &self.sbox,
+            &mut result,
+            key,
+            SOURCE_SIZE,
+            INTRON_SIZE,
+            trace,
+        );
         result
     }
 
     pub fn encrypt(&self, input: Vec<DNA>) -> Vec<DNA> {
+        self.encrypt_blocks(input, None)
+    }
+
+    /// Encrypts `input` like [`DNAC::encrypt`], passing state of every round to `observer`.
+    pub fn encrypt_observed(&self, input: Vec<DNA>, observer: &mut dyn RoundObserver) -> Vec<DNA> {
+        self.encrypt_blocks(input, Some(observer))
+    }
+
+    fn encrypt_blocks(
+        &self,
+        input: Vec<DNA>,
+        mut observer: Option<&mut dyn RoundObserver>,
+    ) -> Vec<DNA> {
         let ciphertext = input
             .chunks(INPUT_SIZE)
-            .flat_map(|chunk| {
+            .enumerate()
+            .flat_map(|(block, chunk)| {
                 let mut input_chunk = [DNA::A; INPUT_SIZE];
-                let mut key_chunks = self.key.iter().peekable();
+                let mut key_chunks = self.key.iter().enumerate().peekable();
 
                 // in case last chunk is shorter than INPUT_SIZE bases the rest will be filled with A's
                 input_chunk[0..chunk.len()].copy_from_slice(chunk);
 
-                while let Some(key_chunk) = key_chunks.next() {
+                while let Some((round, key_chunk)) = key_chunks.next() {
                     // try_into changes slices to arrays of fixed length
-                    let result = self.round(&input_chunk, key_chunk);
+                    let result = match observer.as_mut() {
+                        Some(observer) => {
+                            let mut trace = RoundTrace {
+                                block,
+                                round,
+                                ..Default::default()
+                            };
+                            let result =
+                                self.round_traced(&input_chunk, key_chunk, Some(&mut trace));
+                            trace.output = result[SOURCE_SIZE..INPUT_SIZE].to_vec();
+                            observer.observe(trace);
+                            result
+                        }
+                        None => self.round(&input_chunk, key_chunk),
+                    };
                     if key_chunks.peek().is_some() {
                         // swap head with tail as per the Feistel algorithm
-                        input_chunk[0..TARGET_SIZE]
-                            .copy_from_slice(&result[SOURCE_SIZE..INPUT_SIZE]);
-                        input_chunk[TARGET_SIZE..INPUT_SIZE]
-                            .copy_from_slice(&result[0..SOURCE_SIZE]);
+                        input_chunk = swap(&result);
                     } else {
                         // for last round we need to keep the order to be able to decrypt the message
                         input_chunk = result;
@@ -193,7 +292,7 @@ impl DNAC {
     }
 
     pub fn decrypt(&self, input: Vec<DNA>) -> Result<Vec<DNA>, String> {
-        if input.len() % INPUT_SIZE != 0 {
+        if !input.len().is_multiple_of(INPUT_SIZE) {
             return Err(format!(
                 "illegal input, length should be a multiple of {}",
                 INPUT_SIZE
@@ -229,6 +328,90 @@ impl DNAC {
     }
 }
 
+fn swap(result: &[DNA; INPUT_SIZE]) -> [DNA; INPUT_SIZE] {
+    let mut swapped = [DNA::A; INPUT_SIZE];
+    swapped[0..TARGET_SIZE].copy_from_slice(&result[SOURCE_SIZE..INPUT_SIZE]);
+    swapped[TARGET_SIZE..INPUT_SIZE].copy_from_slice(&result[0..SOURCE_SIZE]);
+    swapped
+}
+
+// Round function on a state with the source block of `source_size` bases followed by
+// the target block, which is changed in place and must not be longer than TARGET_SIZE.
+// Round keys have as many bases as the target block, 2 bases selecting the xor
+// definition and 2-base intron patterns. Also used by scaled-down variants of the cipher.
+pub(crate) fn round_in_place(
+    sbox: &SBox,
+    state: &mut [DNA],
+    key: &[DNA],
+    source_size: usize,
+    intron_size: usize,
+    mut trace: Option<&mut RoundTrace>,
+) {
+    let (source, target) = state.split_at_mut(source_size);
+    let target_size = target.len();
+    let base_key = &key[..target_size];
+    let xor_selector = &key[target_size..target_size + 2];
+    let intron_patterns = &key[target_size + 2..];
+    let intron_patterns = intron_patterns
+        .chunks_exact(2)
+        .map(|c| c.try_into().unwrap())
+        .collect::<Vec<[DNA; 2]>>();
+
+    let mut intron = [DNA::A; TARGET_SIZE];
+    let intron = &mut intron[..target_size];
+    let mut intron_len = 0;
+
+    let mut intron_idx = 0;
+    let mut source_idx = 0;
+    while source_idx < source_size - 1 && intron_idx < 10 {
+        let source_pattern = source[source_idx..source_idx + 2].try_into().unwrap();
+        if intron_patterns.contains(&source_pattern) {
+            // if intron_patterns[intron_idx..intron_idx + 2] == source[source_idx..source_idx + 2] {
+            let cp_len = min!(
+                source_size - 1 - source_idx, // limit to the end of the source block
+                target_size - intron_len,     // limit to the size of target block
+                intron_size
+            );
+            intron[intron_len..intron_len + cp_len]
+                .copy_from_slice(&source[source_idx..source_idx + cp_len]);
+            if let Some(trace) = trace.as_mut() {
+                trace.introns.push((source_idx, cp_len));
+            }
+            intron_len += cp_len;
+            source_idx += cp_len;
+            intron_idx += 2;
+        } else {
+            source_idx += 1;
+        }
+    }
+    trace!("intron_len = {}", intron_len);
+
+    // use last two bases of key to select the xor definition
+    let xor_index = get_xor_index(xor_selector);
+    let dna_xor = XORS[xor_index];
+
+    // transform introns with sbox
+    let mut sbox_output = [DNA::A; TARGET_SIZE];
+    let sbox_output = &mut sbox_output[..target_size];
+    for (output, chunk) in sbox_output.chunks_exact_mut(4).zip(intron.chunks_exact(4)) {
+        output.copy_from_slice(&sbox[chunk.try_into().unwrap()]);
+    }
+
+    if let Some(trace) = trace {
+        trace.source = source.to_vec();
+        trace.target = target.to_vec();
+        trace.xor_table = xor_index;
+        trace.sbox_output = sbox_output.to_vec();
+    }
+
+    sbox_output
+        .iter()
+        .enumerate()
+        .map(|(i, &intron_base)| (i, dna_xor(base_key[i], intron_base)))
+        // order is important - target must be the first argument
+        .for_each(|(i, intron_base)| target[i] = dna_xor(target[i], intron_base));
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -248,4 +431,28 @@ mod test {
         let cipher = DNAC::new_default(key);
         assert_eq!(msg, cipher.decrypt(cipher.encrypt(msg.clone())).unwrap());
     }
+
+    #[test]
+    fn test_encrypt_observed() {
+        let key = rand::thread_rng()
+            .gen::<[u8; 16]>()
+            .iter()
+            .flat_map(binary_to_DNA)
+            .collect::<Vec<DNA>>();
+        let msg = rand::thread_rng()
+            .gen::<[u8; 32]>()
+            .iter()
+            .flat_map(binary_to_DNA)
+            .collect::<Vec<DNA>>();
+        let cipher = DNAC::new(key, 10);
+        let mut recorder = trace::TraceRecorder::default();
+        let encrypted = cipher.encrypt_observed(msg.clone(), &mut recorder);
+        assert_eq!(encrypted, cipher.encrypt(msg));
+        assert_eq!(recorder.rounds.len(), 20);
+        for t in recorder.rounds {
+            assert_eq!(t.source.len(), SOURCE_SIZE);
+            assert_eq!(t.output.len(), TARGET_SIZE);
+            assert!(t.intron_len() <= TARGET_SIZE);
+        }
+    }
 }
@@ -0,0 +1,354 @@
+//! Monte Carlo tests in the style of AESAVS: every record is the result of
+//! [`ITERATIONS`] chained encryptions or decryptions, and the key, IV and text
+//! of the next record are derived from its last outputs. Stored in `tests/data/mct.rsp`.
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    dna::DNA,
+    kat::{parse_sections, render_sections, to_bytes, to_dna, Vector},
+    modes::Mode,
+    DNAC, INPUT_SIZE,
+};
+
+pub const RECORDS: usize = 100;
+pub const ITERATIONS: usize = 1000;
+
+const ROUNDS: usize = 22;
+const MODES: [Mode; 2] = [Mode::Ecb, Mode::Cbc];
+const KEY_SIZES: [usize; 2] = [16, 32];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 2] = [Direction::Encrypt, Direction::Decrypt];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Encrypt => "ENCRYPT",
+            Direction::Decrypt => "DECRYPT",
+        }
+    }
+}
+
+fn xor(a: &[DNA], b: &[DNA]) -> Vec<DNA> {
+    a.iter().zip(b).map(|(&a, &b)| a ^ b).collect()
+}
+
+fn section_name(mode: Mode, direction: Direction) -> String {
+    format!("{} {}", mode.name(), direction.name())
+}
+
+fn by_section_name(name: &str) -> Result<(Mode, Direction), String> {
+    let (mode, direction) = name
+        .split_once(' ')
+        .ok_or(format!("illegal section {}", name))?;
+    let direction = Direction::ALL
+        .into_iter()
+        .find(|d| d.name().eq_ignore_ascii_case(direction))
+        .ok_or(format!("unknown direction {}", direction))?;
+    Ok((Mode::by_name(mode)?, direction))
+}
+
+// outputs of the chained operations of a single record, in CBC the chaining value
+// continues through all of them as if they were blocks of one message
+fn iterate(
+    cipher: &DNAC,
+    mode: Mode,
+    direction: Direction,
+    iv: &[DNA],
+    input: &[DNA],
+    iterations: usize,
+) -> Result<Vec<Vec<DNA>>, String> {
+    let mut outputs: Vec<Vec<DNA>> = Vec::with_capacity(iterations);
+    let mut input = input.to_vec();
+    let mut chain = iv.to_vec();
+    for j in 0..iterations {
+        let output = match (mode, direction) {
+            (Mode::Cbc, Direction::Encrypt) => {
+                chain = cipher.encrypt(xor(&input, &chain));
+                chain.clone()
+            }
+            (Mode::Cbc, Direction::Decrypt) => {
+                let output = xor(&cipher.decrypt(input.clone())?, &chain);
+                chain = input;
+                output
+            }
+            (_, Direction::Encrypt) => cipher.encrypt(input),
+            (_, Direction::Decrypt) => cipher.decrypt(input)?,
+        };
+        input = match mode {
+            Mode::Cbc if j == 0 => iv.to_vec(),
+            Mode::Cbc => outputs[j - 1].clone(),
+            _ => output.clone(),
+        };
+        outputs.push(output);
+    }
+    Ok(outputs)
+}
+
+// key, IV (only used in CBC) and plaintext or ciphertext at the start of a record
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Start {
+    key: Vec<DNA>,
+    iv: Vec<DNA>,
+    input: Vec<DNA>,
+}
+
+impl Start {
+    fn of(vector: &Vector, direction: Direction) -> Start {
+        Start {
+            key: to_dna(&vector.key),
+            iv: to_dna(&vector.iv),
+            input: match direction {
+                Direction::Encrypt => to_dna(&vector.plaintext),
+                Direction::Decrypt => to_dna(&vector.ciphertext),
+            },
+        }
+    }
+}
+
+// a single record and the start of the next one
+fn run_record(
+    mode: Mode,
+    direction: Direction,
+    rounds: usize,
+    count: usize,
+    start: &Start,
+    iterations: usize,
+) -> Result<(Vector, Start), String> {
+    let Start { key, iv, input } = start;
+    let cipher = DNAC::try_new(key.clone(), rounds)?;
+    let outputs = iterate(&cipher, mode, direction, iv, input, iterations)?;
+    let last = &outputs[iterations - 1];
+    let before_last = &outputs[iterations - 2];
+
+    let (plaintext, ciphertext) = match direction {
+        Direction::Encrypt => (to_bytes(input), to_bytes(last)),
+        Direction::Decrypt => (to_bytes(last), to_bytes(input)),
+    };
+    let record = Vector {
+        count,
+        rounds,
+        key: to_bytes(key),
+        iv: if mode == Mode::Cbc {
+            to_bytes(iv)
+        } else {
+            Vec::new()
+        },
+        plaintext,
+        ciphertext,
+    };
+
+    // the key is xored with as many of the last output bases as it has
+    let tail = [before_last.as_slice(), last].concat();
+    let key = xor(key, &tail[tail.len() - key.len()..]);
+    let next = match mode {
+        Mode::Cbc => Start {
+            key,
+            iv: last.clone(),
+            input: before_last.clone(),
+        },
+        _ => Start {
+            key,
+            iv: iv.clone(),
+            input: last.clone(),
+        },
+    };
+    Ok((record, next))
+}
+
+/// Runs `records` records of `iterations` operations each, starting with the given
+/// key, IV (only used in CBC) and plaintext or ciphertext.
+pub fn monte_carlo(
+    mode: Mode,
+    direction: Direction,
+    start: &Vector,
+    records: usize,
+    iterations: usize,
+) -> Result<Vec<Vector>, String> {
+    if mode == Mode::Ctr {
+        return Err("Monte Carlo tests are defined only for ECB and CBC".to_string());
+    }
+    if iterations < 2 {
+        return Err("Monte Carlo tests need at least 2 iterations".to_string());
+    }
+    let mut next = Start::of(start, direction);
+    if next.input.len() != INPUT_SIZE || (mode == Mode::Cbc && next.iv.len() != INPUT_SIZE) {
+        return Err(format!("illegal input, length should be {}", INPUT_SIZE));
+    }
+
+    let mut result = Vec::with_capacity(records);
+    for count in 0..records {
+        let (record, start) = run_record(mode, direction, start.rounds, count, &next, iterations)?;
+        result.push(record);
+        next = start;
+    }
+    Ok(result)
+}
+
+/// Parses records grouped by sections like `[ECB ENCRYPT]`.
+pub fn parse(text: &str) -> Result<Vec<(Mode, Direction, Vector)>, String> {
+    parse_sections(text)?
+        .into_iter()
+        .map(|(section, vector)| {
+            let (mode, direction) = by_section_name(&section)?;
+            Ok((mode, direction, vector))
+        })
+        .collect()
+}
+
+pub fn render(records: &[(Mode, Direction, Vector)]) -> Result<String, std::fmt::Error> {
+    let records = records
+        .iter()
+        .map(|(mode, direction, vector)| (section_name(*mode, *direction), vector.clone()))
+        .collect::<Vec<_>>();
+    render_sections(
+        "DNAC Monte Carlo tests, generated with `dnac kat --mct`",
+        &records,
+    )
+}
+
+/// Records computed with the current implementation from random keys, IVs and texts,
+/// for both directions of ECB and CBC with each key size.
+pub fn generate() -> Result<Vec<(Mode, Direction, Vector)>, String> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut random = |size: usize| {
+        let mut bytes = vec![0; size];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    };
+    let mut records = Vec::new();
+    for mode in MODES {
+        for direction in Direction::ALL {
+            for key_size in KEY_SIZES {
+                let start = Vector {
+                    count: 0,
+                    rounds: ROUNDS,
+                    key: random(key_size),
+                    iv: if mode == Mode::Cbc {
+                        random(16)
+                    } else {
+                        Vec::new()
+                    },
+                    plaintext: random(16),
+                    ciphertext: random(16),
+                };
+                records.extend(
+                    monte_carlo(mode, direction, &start, RECORDS, ITERATIONS)?
+                        .into_iter()
+                        .map(|vector| (mode, direction, vector)),
+                );
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Recomputes every record of `text` from its key, IV and input and checks that every
+/// record with a non-zero count continues the chain of the previous one, returns
+/// the number of records.
+pub fn selftest(text: &str) -> Result<usize, String> {
+    let records = parse(text)?;
+    records
+        .par_iter()
+        .enumerate()
+        .try_for_each(|(i, (mode, direction, record))| {
+            let name = section_name(*mode, *direction);
+            let in_section = |j: usize| {
+                records
+                    .get(j)
+                    .filter(|(m, d, _)| (m, d) == (mode, direction))
+                    .map(|(_, _, vector)| vector)
+            };
+            let previous = i.checked_sub(1).and_then(in_section);
+            if record.count != 0 && previous.map(|p| p.count + 1) != Some(record.count) {
+                return Err(format!("{}: record {} is out of order", name, record.count));
+            }
+
+            let start = Start::of(record, *direction);
+            let (computed, next) = run_record(
+                *mode,
+                *direction,
+                record.rounds,
+                record.count,
+                &start,
+                ITERATIONS,
+            )?;
+            if computed != *record {
+                return Err(format!("{}: record {} does not match", name, record.count));
+            }
+            match in_section(i + 1) {
+                Some(following)
+                    if following.count != 0 && Start::of(following, *direction) != next =>
+                {
+                    Err(format!(
+                        "{}: record {} does not follow from record {}",
+                        name, following.count, record.count
+                    ))
+                }
+                _ => Ok(()),
+            }
+        })?;
+    Ok(records.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decrypt_inverts_encrypt() {
+        let mut rng = rand::thread_rng();
+        let mut random = |size: usize| {
+            let mut bytes = vec![0; size];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        };
+        let start = Vector {
+            count: 0,
+            rounds: 8,
+            key: random(16),
+            iv: Vec::new(),
+            plaintext: random(16),
+            ciphertext: Vec::new(),
+        };
+        // a single ECB record is a chain of encryptions, so decrypting its end walks it back
+        let encrypted = monte_carlo(Mode::Ecb, Direction::Encrypt, &start, 2, 10).unwrap();
+        let decrypted = monte_carlo(Mode::Ecb, Direction::Decrypt, &encrypted[0], 1, 10).unwrap();
+        assert_eq!(decrypted[0].plaintext, start.plaintext);
+        assert_ne!(encrypted[1].key, start.key);
+        assert!(monte_carlo(Mode::Cbc, Direction::Encrypt, &start, 1, 10).is_err());
+    }
+
+    #[test]
+    fn test_selftest_checks_chaining() {
+        let start = |key: u8| Vector {
+            count: 0,
+            rounds: 2,
+            key: vec![key; 16],
+            iv: Vec::new(),
+            plaintext: vec![0; 16],
+            ciphertext: Vec::new(),
+        };
+        let records = |vectors: &[&Vector]| {
+            let records = vectors
+                .iter()
+                .map(|&v| (Mode::Ecb, Direction::Encrypt, v.clone()))
+                .collect::<Vec<_>>();
+            render(&records).unwrap()
+        };
+        let first = monte_carlo(Mode::Ecb, Direction::Encrypt, &start(1), 2, ITERATIONS).unwrap();
+        let other = monte_carlo(Mode::Ecb, Direction::Encrypt, &start(2), 2, ITERATIONS).unwrap();
+        assert_eq!(selftest(&records(&[&first[0], &first[1]])), Ok(2));
+        // both records are correct on their own, but the second one starts another chain
+        assert!(selftest(&records(&[&first[0], &other[1]])).is_err());
+        assert!(selftest(&records(&[&first[1]])).is_err());
+        assert_eq!(selftest(&records(&[&first[0], &other[0]])), Ok(2));
+    }
+}
@@ -0,0 +1,144 @@
+//! Modes of operation built on single block encryption; [`DNAC::encrypt`]
+//! and [`DNAC::decrypt`] of several blocks work as ECB.
+
+use crate::{
+    dna::{binary_to_DNA, DNA_to_binary, DNA},
+    DNAC, INPUT_SIZE,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+    Ctr,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Ecb, Mode::Cbc, Mode::Ctr];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Ecb => "ECB",
+            Mode::Cbc => "CBC",
+            Mode::Ctr => "CTR",
+        }
+    }
+
+    pub fn by_name(name: &str) -> Result<Mode, String> {
+        Mode::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("unknown mode {}", name))
+    }
+}
+
+/// Encrypts `input` in the given mode, `iv` is not used in ECB.
+pub fn encrypt(cipher: &DNAC, mode: Mode, iv: &[DNA], input: Vec<DNA>) -> Result<Vec<DNA>, String> {
+    match mode {
+        Mode::Ecb => {
+            check_input(&input)?;
+            Ok(cipher.encrypt(input))
+        }
+        Mode::Cbc => cbc_encrypt(cipher, iv, input),
+        Mode::Ctr => ctr(cipher, iv, input),
+    }
+}
+
+pub fn decrypt(cipher: &DNAC, mode: Mode, iv: &[DNA], input: Vec<DNA>) -> Result<Vec<DNA>, String> {
+    match mode {
+        Mode::Ecb => cipher.decrypt(input),
+        Mode::Cbc => cbc_decrypt(cipher, iv, input),
+        Mode::Ctr => ctr(cipher, iv, input),
+    }
+}
+
+fn check_iv(iv: &[DNA]) -> Result<(), String> {
+    if iv.len() != INPUT_SIZE {
+        return Err(format!("illegal IV, length should be {}", INPUT_SIZE));
+    }
+    Ok(())
+}
+
+fn check_input(input: &[DNA]) -> Result<(), String> {
+    if !input.len().is_multiple_of(INPUT_SIZE) {
+        return Err(format!(
+            "illegal input, length should be a multiple of {}",
+            INPUT_SIZE
+        ));
+    }
+    Ok(())
+}
+
+fn xor(a: &[DNA], b: &[DNA]) -> Vec<DNA> {
+    a.iter().zip(b).map(|(&a, &b)| a ^ b).collect()
+}
+
+pub fn cbc_encrypt(cipher: &DNAC, iv: &[DNA], input: Vec<DNA>) -> Result<Vec<DNA>, String> {
+    check_iv(iv)?;
+    check_input(&input)?;
+    let mut previous = iv.to_vec();
+    let mut output = Vec::with_capacity(input.len());
+    for block in input.chunks_exact(INPUT_SIZE) {
+        previous = cipher.encrypt(xor(block, &previous));
+        output.extend_from_slice(&previous);
+    }
+    Ok(output)
+}
+
+pub fn cbc_decrypt(cipher: &DNAC, iv: &[DNA], input: Vec<DNA>) -> Result<Vec<DNA>, String> {
+    check_iv(iv)?;
+    check_input(&input)?;
+    let mut previous = iv;
+    let mut output = Vec::with_capacity(input.len());
+    for block in input.chunks_exact(INPUT_SIZE) {
+        output.extend(xor(&cipher.decrypt(block.to_vec())?, previous));
+        previous = block;
+    }
+    Ok(output)
+}
+
+/// Encrypts or decrypts `input` of any length with the keystream of encrypted counter
+/// blocks, the first counter block is `nonce` and the next ones are incremented as
+/// 128-bit big-endian integers.
+pub fn ctr(cipher: &DNAC, nonce: &[DNA], input: Vec<DNA>) -> Result<Vec<DNA>, String> {
+    check_iv(nonce)?;
+    let bytes = nonce
+        .chunks_exact(4)
+        .map(|chunk| DNA_to_binary(chunk.try_into().unwrap()))
+        .collect::<Vec<u8>>();
+    let counter = u128::from_be_bytes(bytes.try_into().unwrap());
+    let mut output = Vec::with_capacity(input.len());
+    for (i, block) in input.chunks(INPUT_SIZE).enumerate() {
+        let counter_block = counter
+            .wrapping_add(i as u128)
+            .to_be_bytes()
+            .iter()
+            .flat_map(binary_to_DNA)
+            .collect();
+        output.extend(xor(block, &cipher.encrypt(counter_block)));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::random_dna;
+
+    #[test]
+    fn test_modes() {
+        let rng = &mut rand::thread_rng();
+        let cipher = DNAC::new(random_dna(rng, 16), 8);
+        let iv = random_dna(rng, 16);
+        let text = random_dna(rng, 48);
+
+        let encrypted = cbc_encrypt(&cipher, &iv, text.clone()).unwrap();
+        assert_eq!(encrypted[..INPUT_SIZE], cipher.encrypt(xor(&text, &iv)));
+        assert_eq!(cbc_decrypt(&cipher, &iv, encrypted).unwrap(), text);
+        assert!(cbc_encrypt(&cipher, &iv, text[1..].to_vec()).is_err());
+
+        let encrypted = ctr(&cipher, &iv, text[3..].to_vec()).unwrap();
+        assert_eq!(encrypted.len(), text.len() - 3);
+        assert_eq!(ctr(&cipher, &iv, encrypted).unwrap(), text[3..]);
+    }
+}
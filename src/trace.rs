@@ -0,0 +1,161 @@
+use crate::dna::DNA;
+
+/// State of a single round of a single block, recorded during
+/// [`DNAC::encrypt_observed`](crate::DNAC::encrypt_observed).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoundTrace {
+    pub block: usize,
+    pub round: usize,
+    pub source: Vec<DNA>,
+    // target half before mixing
+    pub target: Vec<DNA>,
+    // (position in source, length) of every extracted intron
+    pub introns: Vec<(usize, usize)>,
+    // index of the xor definition in dna::xors::XORS
+    pub xor_table: usize,
+    pub sbox_output: Vec<DNA>,
+    // target half after mixing
+    pub output: Vec<DNA>,
+}
+
+impl RoundTrace {
+    pub fn intron_len(&self) -> usize {
+        self.introns.iter().map(|(_, len)| len).sum()
+    }
+
+    pub fn to_json(&self) -> String {
+        let introns = self
+            .introns
+            .iter()
+            .map(|(pos, len)| format!("{{\"position\":{},\"length\":{}}}", pos, len))
+            .collect::<Vec<String>>()
+            .join(",");
+        format!(
+            "{{\"block\":{},\"round\":{},\"source\":\"{}\",\"target\":\"{}\",\"introns\":[{}],\"xor_table\":{},\"sbox_output\":\"{}\",\"output\":\"{}\"}}",
+            self.block,
+            self.round,
+            bases(&self.source),
+            bases(&self.target),
+            introns,
+            self.xor_table,
+            bases(&self.sbox_output),
+            bases(&self.output)
+        )
+    }
+}
+
+/// Receives a [`RoundTrace`] after each round of encryption.
+pub trait RoundObserver {
+    fn observe(&mut self, trace: RoundTrace);
+}
+
+impl<F: FnMut(RoundTrace)> RoundObserver for F {
+    fn observe(&mut self, trace: RoundTrace) {
+        self(trace)
+    }
+}
+
+/// Observer collecting all traces in order of rounds.
+#[derive(Default)]
+pub struct TraceRecorder {
+    pub rounds: Vec<RoundTrace>,
+}
+
+impl RoundObserver for TraceRecorder {
+    fn observe(&mut self, trace: RoundTrace) {
+        self.rounds.push(trace)
+    }
+}
+
+pub fn bases(dna: &[DNA]) -> String {
+    dna.iter().map(|b| b.to_string()).collect()
+}
+
+pub fn render_json(traces: &[RoundTrace]) -> String {
+    let rounds = traces
+        .iter()
+        .map(|t| format!("  {}", t.to_json()))
+        .collect::<Vec<String>>()
+        .join(",\n");
+    format!("[\n{}\n]\n", rounds)
+}
+
+pub fn render_table(traces: &[RoundTrace]) -> String {
+    let header = [
+        "block",
+        "round",
+        "source",
+        "target",
+        "introns (pos:len)",
+        "xor",
+        "sbox output",
+        "output",
+    ];
+    let rows = traces
+        .iter()
+        .map(|t| {
+            let introns = t
+                .introns
+                .iter()
+                .map(|(pos, len)| format!("{}:{}", pos, len))
+                .collect::<Vec<String>>()
+                .join(" ");
+            vec![
+                t.block.to_string(),
+                t.round.to_string(),
+                bases(&t.source),
+                bases(&t.target),
+                introns,
+                // named like the functions, which are numbered from 1
+                format!("dnaxor_{}", t.xor_table + 1),
+                bases(&t.sbox_output),
+                bases(&t.output),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.len());
+        }
+    }
+    let format_row = |row: Vec<&str>| {
+        row.iter()
+            .zip(widths)
+            .map(|(cell, w)| format!("{:<w$}", cell, w = w))
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_row(header.to_vec())];
+    lines.push(
+        widths
+            .iter()
+            .map(|&w| "-".repeat(w))
+            .collect::<Vec<String>>()
+            .join("-+-"),
+    );
+    lines.extend(
+        rows.iter()
+            .map(|row| format_row(row.iter().map(String::as_str).collect())),
+    );
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_xor_table() {
+        let trace = RoundTrace {
+            xor_table: 2,
+            ..Default::default()
+        };
+        assert!(trace.to_json().contains("\"xor_table\":2,"));
+        assert!(render_table(&[trace]).contains("dnaxor_3"));
+    }
+}
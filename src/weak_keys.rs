@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use crate::{
+    dna::{xors::get_xor_index, DNA},
+    sbox::SBox,
+    DNAC, KEY_SIZE, TARGET_SIZE,
+};
+
+// weak if more than half of round keys have repeated intron patterns
+const MAX_DUPLICATE_RATE: f64 = 0.5;
+// a single round key always uses a single xor definition and is distinct
+const MIN_ROUNDS: usize = 2;
+
+/// Weaknesses found in a key after expanding it for given number of rounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyReport {
+    // fraction of round keys with at least two equal intron patterns
+    pub duplicate_patterns: f64,
+    // fraction of rounds using the most common xor definition
+    pub xor_concentration: f64,
+    // number of distinct round keys divided by number of rounds
+    pub round_key_diversity: f64,
+}
+
+impl KeyReport {
+    pub fn degenerate_xor(&self) -> bool {
+        self.xor_concentration >= 1.0
+    }
+
+    /// Score in range 0..=1, 0 means no weakness was found.
+    pub fn score(&self) -> f64 {
+        (self.duplicate_patterns + self.xor_concentration + (1.0 - self.round_key_diversity)) / 3.0
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.duplicate_patterns > MAX_DUPLICATE_RATE
+            || self.degenerate_xor()
+            || self.round_key_diversity < 1.0
+    }
+}
+
+/// Decides whether [`DNAC::new_with_policy`] accepts weak keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyPolicy {
+    AllowWeak,
+    RejectWeak,
+}
+
+/// Expands `key` with the default schedule and looks for weaknesses in its round keys,
+/// returning an error for key sizes rejected by [`DNAC::try_new`] and for fewer than
+/// 2 rounds, where the statistics over round keys are meaningless.
+pub fn check_key(key: &[DNA], rounds: usize) -> Result<KeyReport, String> {
+    DNAC::check_key_size(key.len())?;
+    if rounds < MIN_ROUNDS {
+        return Err(format!(
+            "weak keys can be detected only for at least {} rounds",
+            MIN_ROUNDS
+        ));
+    }
+    let round_keys = DNAC::expand_key(key.to_vec(), SBox::new(), rounds);
+    let n = round_keys.len() as f64;
+
+    let duplicates = round_keys
+        .iter()
+        .filter(|round_key| {
+            let patterns = round_key[TARGET_SIZE + 2..KEY_SIZE]
+                .chunks_exact(2)
+                .collect::<Vec<&[DNA]>>();
+            patterns.iter().collect::<HashSet<_>>().len() < patterns.len()
+        })
+        .count();
+
+    let mut xors = [0; 6];
+    for round_key in round_keys.iter() {
+        xors[get_xor_index(&round_key[TARGET_SIZE..TARGET_SIZE + 2])] += 1;
+    }
+
+    let distinct = round_keys.iter().collect::<HashSet<_>>().len();
+
+    Ok(KeyReport {
+        duplicate_patterns: duplicates as f64 / n,
+        xor_concentration: *xors.iter().max().unwrap() as f64 / n,
+        round_key_diversity: distinct as f64 / n,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dna::binary_to_DNA;
+
+    #[test]
+    fn test_check_key() {
+        let weak = vec![DNA::A; 64];
+        assert!(check_key(&weak, 22).unwrap().is_weak());
+        assert!(check_key(&weak[..60], 22).is_err());
+        assert!(DNAC::new_with_policy(weak, 22, KeyPolicy::RejectWeak).is_err());
+
+        let key = (0..16u8)
+            .map(|i| i.wrapping_mul(73).wrapping_add(41))
+            .flat_map(|b| binary_to_DNA(&b))
+            .collect::<Vec<DNA>>();
+        assert!(!check_key(&key, 22).unwrap().is_weak());
+        assert!(DNAC::new_with_policy(key.clone(), 22, KeyPolicy::RejectWeak).is_ok());
+        assert!(check_key(&key, 2).is_ok());
+        for rounds in [0, 1] {
+            assert!(check_key(&key, rounds).is_err());
+            assert!(DNAC::new_with_policy(key.clone(), rounds, KeyPolicy::RejectWeak).is_err());
+        }
+    }
+}
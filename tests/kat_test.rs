@@ -0,0 +1,19 @@
+use dnac::kat::{generate, parse, selftest};
+
+const VECTORS: &str = include_str!("data/kat.rsp");
+const MCT_VECTORS: &str = include_str!("data/mct.rsp");
+
+#[test]
+fn known_answer_tests() {
+    assert_eq!(selftest(VECTORS), Ok(22));
+}
+
+#[test]
+fn known_answer_tests_up_to_date() {
+    assert_eq!(parse(VECTORS).unwrap(), generate().unwrap());
+}
+
+#[test]
+fn monte_carlo_tests() {
+    assert_eq!(dnac::mct::selftest(MCT_VECTORS), Ok(800));
+}
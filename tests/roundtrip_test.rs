@@ -0,0 +1,101 @@
+use dnac::{
+    dna::DNA,
+    key_schedule::{AesSchedule, CtrSchedule, KeySchedule, SpongeSchedule},
+    modes, DNAC,
+};
+use proptest::{collection::vec, prelude::*};
+
+const INPUT_SIZE: usize = 64;
+const MAX_ROUNDS: usize = 40;
+
+fn base() -> impl Strategy<Value = DNA> {
+    prop_oneof![Just(DNA::A), Just(DNA::C), Just(DNA::G), Just(DNA::T)]
+}
+
+fn bases(max: usize) -> impl Strategy<Value = Vec<DNA>> {
+    vec(base(), 0..=max)
+}
+
+// keys accepted by the default schedule: 4 to 16 words of 16 bases
+fn key() -> impl Strategy<Value = Vec<DNA>> {
+    (4..=16usize).prop_flat_map(|words| vec(base(), words * 16))
+}
+
+fn block() -> impl Strategy<Value = Vec<DNA>> {
+    vec(base(), INPUT_SIZE)
+}
+
+// encryption pads the last block with A's
+fn padded(msg: &[DNA]) -> Vec<DNA> {
+    let mut padded = msg.to_vec();
+    padded.resize(msg.len().div_ceil(INPUT_SIZE) * INPUT_SIZE, DNA::A);
+    padded
+}
+
+proptest! {
+    #[test]
+    fn encrypt_decrypt(key in key(), rounds in 0..=MAX_ROUNDS, msg in bases(300)) {
+        let cipher = DNAC::try_new(key, rounds).unwrap();
+        let encrypted = cipher.encrypt(msg.clone());
+        prop_assert_eq!(encrypted.len(), padded(&msg).len());
+        prop_assert_eq!(cipher.decrypt(encrypted).unwrap(), padded(&msg));
+    }
+
+    #[test]
+    fn key_sizes(key in bases(300), rounds in 0..=MAX_ROUNDS, msg in block()) {
+        let valid = key.len() >= 64 && key.len() % 16 == 0;
+        match DNAC::try_new(key, rounds) {
+            Ok(cipher) => {
+                prop_assert!(valid);
+                prop_assert_eq!(cipher.decrypt(cipher.encrypt(msg.clone())).unwrap(), msg);
+            }
+            Err(_) => prop_assert!(!valid),
+        }
+    }
+
+    #[test]
+    fn constant_time(key in key(), rounds in 0..=MAX_ROUNDS, msg in bases(300)) {
+        let reference = DNAC::new(key.clone(), rounds);
+        let constant_time = DNAC::new(key, rounds).constant_time();
+        let encrypted = constant_time.encrypt(msg.clone());
+        prop_assert_eq!(&encrypted, &reference.encrypt(msg));
+        prop_assert_eq!(constant_time.decrypt(encrypted.clone()), reference.decrypt(encrypted));
+    }
+
+    #[test]
+    fn decrypt_any_length(key in key(), msg in bases(300)) {
+        let cipher = DNAC::new_default(key);
+        prop_assert_eq!(cipher.decrypt(msg.clone()).is_ok(), msg.len() % INPUT_SIZE == 0);
+    }
+
+    #[test]
+    fn schedules(key in key(), rounds in 1..=MAX_ROUNDS, msg in block()) {
+        let schedules: [&dyn KeySchedule; 3] = [
+            &AesSchedule,
+            &CtrSchedule::default(),
+            &SpongeSchedule::default(),
+        ];
+        for schedule in schedules {
+            let cipher = DNAC::with_schedule(key.clone(), rounds, schedule).unwrap();
+            prop_assert_eq!(cipher.decrypt(cipher.encrypt(msg.clone())).unwrap(), msg.clone());
+        }
+    }
+
+    #[test]
+    fn sponge_any_key_size(key in bases(300), rounds in 1..=MAX_ROUNDS, msg in block()) {
+        let cipher = DNAC::with_schedule(key, rounds, &SpongeSchedule::default()).unwrap();
+        prop_assert_eq!(cipher.decrypt(cipher.encrypt(msg.clone())).unwrap(), msg);
+    }
+
+    #[test]
+    fn modes_round_trip(key in key(), iv in block(), msg in bases(300)) {
+        let cipher = DNAC::new(key, 8);
+        for mode in modes::Mode::ALL {
+            // only CTR accepts messages which are not a whole number of blocks
+            let input = if mode == modes::Mode::Ctr { msg.clone() } else { padded(&msg) };
+            let encrypted = modes::encrypt(&cipher, mode, &iv, input.clone()).unwrap();
+            prop_assert_eq!(encrypted.len(), input.len());
+            prop_assert_eq!(modes::decrypt(&cipher, mode, &iv, encrypted).unwrap(), input);
+        }
+    }
+}